@@ -1,26 +1,32 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::{Input, ParseError};
 use std::time::Instant;
 
-fn part1() -> u16 {
-    let file = include_str!("input.txt").lines();
+fn parse(input: &str) -> Result<Vec<&str>, ParseError> {
+    let input = Input::new(input);
 
-    0
+    input.parse_lines(input.as_str(), Ok)
 }
 
-fn part2() -> u16 {
-    let file = include_str!("input.txt").lines();
+fn part1(input: &str) -> Result<usize, ParseError> {
+    Ok(parse(input)?.len())
+}
 
-    0
+fn part2(input: &str) -> Result<usize, ParseError> {
+    Ok(parse(input)?.len())
 }
 
-pub fn main() {
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day00");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 00 ---");
@@ -31,21 +37,47 @@ pub fn main() {
 
     // assert_eq!(part1, 0);
     // assert_eq!(part2, 0);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
     use test::Bencher;
 
+    const EXAMPLE: &str = "\
+";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(0), part1(EXAMPLE));
+        assert_eq!(Ok(0), part2(EXAMPLE));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, part1);
+        fuzz::assert_no_panic(EXAMPLE, part2);
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day00") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day00") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
@@ -1,45 +1,49 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::{Input, ParseError};
+use itertools::Itertools;
 use std::time::Instant;
 
-fn part1() -> u32 {
-    let file = include_str!("input.txt");
+fn parse(input: &str) -> Result<Vec<(usize, usize)>, ParseError> {
+    let input = Input::new(input);
 
-    let mut v1 = Vec::with_capacity(1000);
-    let mut v2 = Vec::with_capacity(1000);
+    input.parse_lines(input.as_str(), |line| {
+        let (lhs, rhs) = input.split_once(line, " ")?;
 
-    for line in file.lines() {
-        v1.push(line[0..5].parse::<u32>().unwrap());
-        v2.push(line[8..].parse::<u32>().unwrap());
-    }
+        Ok((input.parse(lhs)?, input.parse(rhs.trim_start())?))
+    })
+}
+
+fn part1(input: &str) -> Result<usize, ParseError> {
+    let (mut v1, mut v2): (Vec<_>, Vec<_>) = parse(input)?.into_iter().unzip();
 
     v1.sort_unstable();
     v2.sort_unstable();
 
-    v1.iter().zip(&v2).map(|(i1, i2)| i1.abs_diff(*i2)).sum()
+    Ok(v1.iter().zip(&v2).map(|(i1, i2)| i1.abs_diff(*i2)).sum())
 }
 
-fn part2() -> usize {
-    let file = include_str!("input.txt");
+fn part2(input: &str) -> Result<usize, ParseError> {
+    let pairs = parse(input)?;
 
-    let mut v1 = Vec::with_capacity(1000);
-    let mut counts = [0; 100_000];
+    let counts = pairs.iter().map(|&(_, i2)| i2).counts();
 
-    for line in file.lines() {
-        v1.push(line[0..5].parse::<usize>().unwrap());
-        counts[line[8..].parse::<usize>().unwrap()] += 1;
-    }
-
-    v1.iter().map(|i1| i1 * counts[*i1]).sum::<usize>()
+    Ok(pairs
+        .iter()
+        .map(|(i1, _)| i1 * counts.get(i1).unwrap_or(&0))
+        .sum::<usize>())
 }
 
-pub fn main() {
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day01");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 01 ---");
@@ -50,21 +54,53 @@ pub fn main() {
 
     assert_eq!(part1, 1646452);
     assert_eq!(part2, 23609874);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
     use test::Bencher;
 
+    const EXAMPLE: &str = "\
+3   4
+4   3
+2   5
+1   3
+3   9
+3   3
+";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(11), part1(EXAMPLE));
+        assert_eq!(Ok(31), part2(EXAMPLE));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, part1);
+        fuzz::assert_no_panic(EXAMPLE, part2);
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day01") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day01") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
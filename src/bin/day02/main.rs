@@ -1,36 +1,50 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::{Input, ParseError};
 use itertools::Itertools;
 use std::time::Instant;
 
-fn check(diffs: &[i16]) -> bool {
+fn check(diffs: &[i32]) -> bool {
     diffs.iter().map(|diff| diff.signum()).all_equal()
         && diffs.iter().all(|&diff| 1 <= diff.abs() && diff.abs() <= 3)
 }
 
-fn part1() -> usize {
-    include_str!("input.txt")
-        .lines()
-        .filter_map(|line| {
-            line.split_ascii_whitespace()
+// Levels are widened so that no difference between them can overflow.
+fn parse(input: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+    let input = Input::new(input);
+
+    input.parse_lines(input.as_str(), |line| {
+        line.split_ascii_whitespace()
+            .map(|s| input.parse::<i16>(s).map(i32::from))
+            .collect()
+    })
+}
+
+fn part1(input: &str) -> Result<usize, ParseError> {
+    Ok(parse(input)?
+        .iter()
+        .filter(|levels| {
+            levels
+                .iter()
                 .tuple_windows()
-                .try_fold(0, |signum, (s1, s2)| {
-                    let diff = s2.parse::<i16>().ok()? - s1.parse::<i16>().ok()?;
+                .try_fold(0, |signum, (i1, i2)| {
+                    let diff = i2 - i1;
 
                     (1 <= diff.abs() && diff.abs() <= 3 && (signum == 0 || diff.signum() == signum))
                         .then_some(diff.signum())
                 })
+                .is_some()
         })
-        .count()
+        .count())
 }
 
-fn part2() -> u16 {
+fn part2(input: &str) -> Result<u16, ParseError> {
     let mut ans = 0;
 
-    for line in include_str!("input.txt").lines() {
-        let diffs = line
-            .split_ascii_whitespace()
-            .filter_map(|s| s.parse::<i16>().ok())
+    for levels in parse(input)? {
+        let diffs = levels
+            .iter()
             .tuple_windows()
             .map(|(i1, i2)| i2 - i1)
             .collect_vec();
@@ -51,16 +65,18 @@ fn part2() -> u16 {
         }
     }
 
-    ans
+    Ok(ans)
 }
 
-pub fn main() {
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day02");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 02 ---");
@@ -71,21 +87,96 @@ pub fn main() {
 
     assert_eq!(part1, 631);
     assert_eq!(part2, 665);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
+    use proptest::prelude::*;
     use test::Bencher;
 
+    const EXAMPLE: &str = "\
+7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9
+";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(2), part1(EXAMPLE));
+        assert_eq!(Ok(4), part2(EXAMPLE));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, part1);
+        fuzz::assert_no_panic(EXAMPLE, part2);
+    }
+
+    fn is_safe(levels: &[i16]) -> bool {
+        levels.windows(2).all(|w| (1..=3).contains(&(w[1] - w[0])))
+            || levels.windows(2).all(|w| (1..=3).contains(&(w[0] - w[1])))
+    }
+
+    // Tries removing every level in turn.
+    fn part2_oracle(input: &str) -> u16 {
+        input
+            .lines()
+            .filter(|line| {
+                let levels = line
+                    .split_ascii_whitespace()
+                    .map(|s| s.parse::<i16>().unwrap())
+                    .collect_vec();
+
+                is_safe(&levels)
+                    || (0..levels.len()).any(|i| {
+                        let mut levels = levels.clone();
+                        levels.remove(i);
+                        is_safe(&levels)
+                    })
+            })
+            .count() as u16
+    }
+
+    // Levels are kept close together so that safe reports are common.
+    fn reports() -> impl Strategy<Value = String> {
+        prop::collection::vec(prop::collection::vec(1..10i16, 2..8), 1..20).prop_map(|reports| {
+            reports
+                .iter()
+                .map(|levels| levels.iter().join(" "))
+                .join("\n")
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_part2_matches_oracle(input in reports()) {
+            prop_assert_eq!(Ok(part2_oracle(&input)), part2(&input));
+        }
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day02") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day02") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
@@ -1,11 +1,10 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 
+use advent_of_code_2024::input;
 use regex::Regex;
 use std::time::Instant;
 
-fn part1() -> u32 {
-    let file = include_str!("input.txt");
-
+fn part1(file: &str) -> u32 {
     let re = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap();
 
     re.captures_iter(file)
@@ -19,9 +18,7 @@ fn part1() -> u32 {
         .sum()
 }
 
-fn part2() -> u32 {
-    let file = include_str!("input.txt");
-
+fn part2(file: &str) -> u32 {
     let re = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)|do\(\)|don't\(\)").unwrap();
 
     let mut enabled = true;
@@ -52,12 +49,14 @@ fn part2() -> u32 {
 }
 
 pub fn main() {
+    let input = input::read("day03");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input);
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input);
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 03 ---");
@@ -74,15 +73,42 @@ pub fn main() {
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
     use test::Bencher;
 
+    const EXAMPLE1: &str =
+        "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+    const EXAMPLE2: &str =
+        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(161, part1(EXAMPLE1));
+        assert_eq!(48, part2(EXAMPLE2));
+    }
+
+    // Any text is corrupted memory, so there is nothing to reject, only nothing to panic on.
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE1, part1);
+        fuzz::assert_no_panic(EXAMPLE2, part2);
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day03") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day03") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
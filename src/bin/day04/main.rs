@@ -1,19 +1,11 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 
-use itertools::Itertools;
+use advent_of_code_2024::grid::Grid;
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::ParseError;
+use advent_of_code_2024::point::Point;
 use std::time::Instant;
 
-const DIRECTIONS: [(isize, isize); 8] = [
-    (-1, -1),
-    (-1, 0),
-    (-1, 1),
-    (0, -1),
-    (0, 1),
-    (1, -1),
-    (1, 0),
-    (1, 1),
-];
-
 const PART2_VALID_PATTERNS: [[(isize, isize, char); 4]; 4] = [
     [(-1, -1, 'M'), (1, 1, 'S'), (1, -1, 'M'), (-1, 1, 'S')],
     [(-1, -1, 'S'), (1, 1, 'M'), (1, -1, 'M'), (-1, 1, 'S')],
@@ -21,81 +13,62 @@ const PART2_VALID_PATTERNS: [[(isize, isize, char); 4]; 4] = [
     [(-1, -1, 'S'), (1, 1, 'M'), (1, -1, 'S'), (-1, 1, 'M')],
 ];
 
-fn read_grid() -> Vec<Vec<char>> {
-    include_str!("input.txt")
-        .lines()
-        .map(|line| line.chars().collect_vec())
-        .collect_vec()
+fn get(grid: &Grid<char>, point: Point) -> Option<char> {
+    grid.get(point).copied()
 }
 
-fn count_matches_part1(grid: &[Vec<char>], i: usize, j: usize) -> usize {
-    if grid[i][j] != 'X' {
+fn count_matches_part1(grid: &Grid<char>, pos: Point) -> usize {
+    if get(grid, pos) != Some('X') {
         return 0;
     }
 
-    DIRECTIONS
+    Point::ADJACENT
         .iter()
-        .filter(|(di, dj)| {
-            ['M', 'A', 'S'].iter().enumerate().all(|(n, c)| {
-                let Some((ii, jj)) = i
-                    .checked_add_signed(di * (n as isize + 1))
-                    .zip(j.checked_add_signed(dj * (n as isize + 1)))
-                else {
-                    return false;
-                };
-
-                let Some(cc) = grid.get(ii).and_then(|line| line.get(jj)) else {
-                    return false;
-                };
-
-                c == cc
-            })
+        .filter(|&&dir| {
+            ['M', 'A', 'S']
+                .iter()
+                .enumerate()
+                .all(|(n, &c)| get(grid, pos + dir * (n as isize + 1)) == Some(c))
         })
         .count()
 }
 
-fn part1() -> usize {
-    let grid = read_grid();
+fn part1(input: &str) -> Result<usize, ParseError> {
+    let grid = Grid::parse(input)?;
 
-    (0..grid.len())
-        .flat_map(|i| (0..grid.len()).map(move |j| (i, j)))
-        .map(|(i, j)| count_matches_part1(&grid, i, j))
-        .sum()
+    Ok(grid
+        .points()
+        .map(|pos| count_matches_part1(&grid, pos))
+        .sum())
 }
 
-fn is_valid_cross_part2(grid: &[Vec<char>], i: usize, j: usize) -> bool {
-    grid[i][j] == 'A'
+fn is_valid_cross_part2(grid: &Grid<char>, pos: Point) -> bool {
+    get(grid, pos) == Some('A')
         && PART2_VALID_PATTERNS.iter().any(|pattern| {
-            pattern.iter().all(|&(di, dj, c)| {
-                let Some((ii, jj)) = i.checked_add_signed(di).zip(j.checked_add_signed(dj)) else {
-                    return false;
-                };
-
-                let Some(cc) = grid.get(ii).and_then(|line| line.get(jj)) else {
-                    return false;
-                };
-
-                &c == cc
-            })
+            pattern
+                .iter()
+                .all(|&(di, dj, c)| get(grid, pos + Point::new(di, dj)) == Some(c))
         })
 }
 
-fn part2() -> usize {
-    let grid = read_grid();
+fn part2(input: &str) -> Result<usize, ParseError> {
+    let grid = Grid::parse(input)?;
 
-    (0..grid.len())
-        .flat_map(|i| (0..grid.len()).map(move |j| (i, j)))
-        .filter(|&(i, j)| is_valid_cross_part2(&grid, i, j))
-        .count()
+    Ok(grid
+        .points()
+        .filter(|&pos| is_valid_cross_part2(&grid, pos))
+        .count())
 }
 
-pub fn main() {
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day04");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 04 ---");
@@ -106,21 +79,57 @@ pub fn main() {
 
     assert_eq!(part1, 2514);
     assert_eq!(part2, 1888);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
     use test::Bencher;
 
+    const EXAMPLE: &str = "\
+MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX
+";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(18), part1(EXAMPLE));
+        assert_eq!(Ok(9), part2(EXAMPLE));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, part1);
+        fuzz::assert_no_panic(EXAMPLE, part2);
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day04") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day04") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
@@ -1,34 +1,53 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::{Input, ParseError};
 use itertools::Itertools;
 use std::cmp::Ordering;
 use std::time::Instant;
 
-fn calculate(part1: bool) -> usize {
-    let (rules, pages) = include_str!("input.txt").split_once("\n\n").unwrap();
+// A page that must come before another.
+type Rule = (usize, usize);
 
-    let mut orderings = [[Ordering::Equal; 100]; 100];
+// Rules, then the updates as lists of pages.
+fn parse(input: &str) -> Result<(Vec<Rule>, Vec<Vec<usize>>), ParseError> {
+    let input = Input::new(input);
+    let [rules, pages] = input.sections_n()?;
+
+    let page = |s| {
+        let page = input.parse(s)?;
+
+        if page < 100 {
+            Ok(page)
+        } else {
+            Err(input.error(s, "a page number below 100"))
+        }
+    };
+
+    let rules = input.parse_lines(rules, |line| {
+        let (i1, i2) = input.split_once(line, "|")?;
+
+        Ok((page(i1)?, page(i2)?))
+    })?;
 
-    for mut chunk in &rules
-        .split(&['\n', '|'])
-        .filter_map(|s| s.parse::<usize>().ok())
-        .chunks(2)
-    {
-        let i1 = chunk.next().unwrap();
-        let i2 = chunk.next().unwrap();
+    let pages = input.parse_lines(pages, |line| line.split(',').map(page).collect())?;
+
+    Ok((rules, pages))
+}
+
+fn calculate(input: &str, part1: bool) -> Result<usize, ParseError> {
+    let (rules, pages) = parse(input)?;
+
+    let mut orderings = [[Ordering::Equal; 100]; 100];
 
+    for (i1, i2) in rules {
         orderings[i1][i2] = Ordering::Less;
         orderings[i2][i1] = Ordering::Greater;
     }
 
     let mut ans = 0;
 
-    for page in pages.lines() {
-        let list = page
-            .split(',')
-            .filter_map(|item| item.parse::<usize>().ok())
-            .collect_vec();
-
+    for list in pages {
         let list2 = list
             .iter()
             .sorted_unstable_by(|&&lhs, &&rhs| orderings[lhs][rhs])
@@ -40,24 +59,26 @@ fn calculate(part1: bool) -> usize {
         }
     }
 
-    ans
+    Ok(ans)
 }
 
-fn part1() -> usize {
-    calculate(true)
+fn part1(input: &str) -> Result<usize, ParseError> {
+    calculate(input, true)
 }
 
-fn part2() -> usize {
-    calculate(false)
+fn part2(input: &str) -> Result<usize, ParseError> {
+    calculate(input, false)
 }
 
-pub fn main() {
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day05");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 05 ---");
@@ -68,21 +89,75 @@ pub fn main() {
 
     assert_eq!(part1, 5275);
     assert_eq!(part2, 6191);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
     use test::Bencher;
 
+    const EXAMPLE: &str = "\
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47
+";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(143), part1(EXAMPLE));
+        assert_eq!(Ok(123), part2(EXAMPLE));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, part1);
+        fuzz::assert_no_panic(EXAMPLE, part2);
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day05") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day05") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
@@ -1,97 +1,115 @@
-#![feature(test)]
-
-use itertools::Itertools;
-use num::Complex;
-use std::collections::HashSet;
+#![cfg_attr(test, feature(test))]
+
+use advent_of_code_2024::bitset::BitGrid;
+use advent_of_code_2024::cycle;
+use advent_of_code_2024::grid::Grid;
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::{Input, ParseError};
+use advent_of_code_2024::point::{Direction, Point};
+use advent_of_code_2024::render::{Canvas, Colour, Style};
+use std::error::Error;
 use std::time::Instant;
 
-const N: isize = 130;
-
-fn parse() -> (HashSet<Complex<isize>>, Complex<isize>) {
-    let mut piles = HashSet::new();
+fn parse(input: &str) -> Result<(BitGrid, Point), ParseError> {
+    let grid = Grid::parse_with(input, "'.', '#' or '^'", |c| {
+        matches!(c, '.' | '#' | '^').then_some(c)
+    })?;
 
-    let mut guard = Complex::ZERO;
+    let guard = grid
+        .position(|&c| c == '^')
+        .ok_or_else(|| Input::new(input).error(input, "a guard '^'"))?;
 
-    for (x, line) in include_str!("input.txt").lines().enumerate() {
-        for (y, c) in line.chars().enumerate() {
-            let point = Complex::new(x as isize, y as isize);
+    let mut piles = BitGrid::new(grid.rows(), grid.cols());
 
-            if c == '#' {
-                piles.insert(point);
-            } else if c == '^' {
-                guard = point;
-            }
+    for (point, &c) in grid.iter() {
+        if c == '#' {
+            piles.insert(point);
         }
     }
 
-    (piles, guard)
+    Ok((piles, guard))
 }
 
-fn simulate_paths(
-    piles: &HashSet<Complex<isize>>,
-    mut guard: Complex<isize>,
-) -> HashSet<Complex<isize>> {
-    let mut dir = -Complex::ONE;
-    let mut path = HashSet::new();
+fn step(
+    piles: &BitGrid,
+    new_pile: Option<Point>,
+    (guard, dir): (Point, Direction),
+) -> Option<(Point, Direction)> {
+    let next = guard + dir;
 
-    while (0..N).contains(&guard.re) && (0..N).contains(&guard.im) {
-        path.insert(guard);
+    if piles.contains(next) || Some(next) == new_pile {
+        return Some((guard, dir.turn_right()));
+    }
 
-        if piles.contains(&(guard + dir)) {
-            dir *= -Complex::I;
-            continue;
-        }
+    next.in_bounds(piles.rows(), piles.cols())
+        .then_some((next, dir))
+}
 
-        guard += dir;
+fn simulate_paths(piles: &BitGrid, guard: Point) -> BitGrid {
+    let mut state = Some((guard, Direction::Up));
+    let mut path = BitGrid::new(piles.rows(), piles.cols());
+
+    while let Some((guard, dir)) = state {
+        path.insert(guard);
+        state = step(piles, None, (guard, dir));
     }
 
     path
 }
 
-fn part1() -> usize {
-    let (piles, guard) = parse();
+fn part1(input: &str) -> Result<usize, ParseError> {
+    let (piles, guard) = parse(input)?;
 
-    simulate_paths(&piles, guard).len()
+    Ok(simulate_paths(&piles, guard).len())
 }
 
-fn part2() -> usize {
-    let (piles, original_guard) = parse();
-
-    let path = simulate_paths(&piles, original_guard);
-
-    let mut loops = 0;
-    let mut visited = HashSet::new();
-
-    for &new_pile in path.iter().unique() {
-        let mut guard = original_guard;
-        let mut dir = -Complex::ONE;
-        visited.clear();
+fn part2(input: &str) -> Result<usize, ParseError> {
+    let (piles, guard) = parse(input)?;
+
+    Ok(simulate_paths(&piles, guard)
+        .iter()
+        .filter(|&new_pile| {
+            cycle::brent((guard, Direction::Up), |&state| {
+                step(&piles, Some(new_pile), state)
+            })
+            .is_some()
+        })
+        .count())
+}
 
-        while (0..N).contains(&guard.re) && (0..N).contains(&guard.im) {
-            if !visited.insert((guard, dir)) {
-                loops += 1;
-                break;
-            }
+// The guard's walk over the lab.
+fn render(input: &str) -> Result<Canvas, ParseError> {
+    let (piles, guard) = parse(input)?;
 
-            if piles.contains(&(guard + dir)) || guard + dir == new_pile {
-                dir *= -Complex::I;
-                continue;
-            }
+    let grid = Grid::from_fn(piles.rows(), piles.cols(), |point| piles.contains(point));
 
-            guard += dir;
+    let mut canvas = Canvas::new(&grid, |_, &pile| {
+        if pile {
+            Style::new('#', Colour::GREY)
+        } else {
+            Style::new('.', Colour::DARK_GREY)
         }
-    }
+    });
+
+    canvas
+        .overlay(
+            simulate_paths(&piles, guard).iter(),
+            Style::new('X', Colour::YELLOW),
+        )
+        .overlay([guard], Style::new('^', Colour::RED));
 
-    loops
+    Ok(canvas)
 }
 
-pub fn main() {
+pub fn main() -> Result<(), Box<dyn Error>> {
+    let input = input::read("day06");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 06 ---");
@@ -100,23 +118,63 @@ pub fn main() {
     println!("Part 1 took: {:.2?}", part1_elapsed);
     println!("Part 2 took: {:.2?}", part2_elapsed);
 
+    if let Some(path) = std::env::args().nth(1) {
+        render(&input)?.save(path, 4)?;
+    }
+
     assert_eq!(part1, 5145);
     assert_eq!(part2, 1523);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
     use test::Bencher;
 
+    const EXAMPLE: &str = "\
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(41), part1(EXAMPLE));
+        assert_eq!(Ok(6), part2(EXAMPLE));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, part1);
+        fuzz::assert_no_panic(EXAMPLE, part2);
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day06") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day06") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
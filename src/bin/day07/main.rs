@@ -1,24 +1,32 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 
-use itertools::Itertools;
+use advent_of_code_2024::input;
+use advent_of_code_2024::number;
+use advent_of_code_2024::parse::{Input, ParseError};
 use std::time::Instant;
 
-const OPERATIONS: [fn(u64, u64) -> u64; 3] = [
-    |i, j| i + j,
-    |i, j| i * j,
-    |i, j| i * 10u64.pow(j.checked_ilog10().unwrap_or(0) + 1) + j,
-];
+const OPERATIONS: [fn(u64, u64) -> Option<u64>; 3] =
+    [u64::checked_add, u64::checked_mul, |i, j| {
+        number::checked_concat(i, j, 10)
+    }];
 
-fn parse() -> impl Iterator<Item = (u64, Vec<u64>)> {
-    include_str!("input.txt").lines().map(|line| {
-        let mut ns = line
-            .split(&[':', ' '])
-            .filter_map(|s| s.parse::<u64>().ok());
+// Test values with their operands.
+fn parse(input: &str) -> Result<Vec<(u64, Vec<u64>)>, ParseError> {
+    let input = Input::new(input);
 
-        (ns.next().unwrap(), ns.collect_vec())
+    input.parse_lines(input.as_str(), |line| {
+        let (test_value, numbers) = input.split_once(line, ": ")?;
+
+        let numbers = numbers
+            .split(' ')
+            .map(|s| input.parse(s))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((input.parse(test_value)?, numbers))
     })
 }
 
+// Every result that does not overflow, which a test value could not be.
 fn operate(numbers: &[u64], part1: bool) -> Vec<u64> {
     let mut numbers = numbers.to_owned();
 
@@ -32,40 +40,38 @@ fn operate(numbers: &[u64], part1: bool) -> Vec<u64> {
 
     for num in operate(&numbers, part1) {
         for op in OPERATIONS[..(if part1 { 2 } else { 3 })].iter() {
-            results.push(op(num, tail));
+            results.extend(op(num, tail));
         }
     }
 
     results
 }
 
-fn part1() -> u64 {
-    parse()
-        .filter_map(|(test_value, numbers)| {
-            operate(&numbers, true)
-                .contains(&test_value)
-                .then_some(test_value)
-        })
-        .sum()
+fn calibrate(input: &str, part1: bool) -> Result<u64, ParseError> {
+    parse(input)?
+        .into_iter()
+        .filter(|(test_value, numbers)| operate(numbers, part1).contains(test_value))
+        .try_fold(0u64, |sum, (test_value, _)| sum.checked_add(test_value))
+        .ok_or_else(|| Input::new(input).error(input, "a total that fits in a u64"))
+}
+
+fn part1(input: &str) -> Result<u64, ParseError> {
+    calibrate(input, true)
 }
 
-fn part2() -> u64 {
-    parse()
-        .filter_map(|(test_value, numbers)| {
-            operate(&numbers, false)
-                .contains(&test_value)
-                .then_some(test_value)
-        })
-        .sum()
+fn part2(input: &str) -> Result<u64, ParseError> {
+    calibrate(input, false)
 }
 
-pub fn main() {
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day07");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 07 ---");
@@ -76,21 +82,63 @@ pub fn main() {
 
     assert_eq!(part1, 3119088655389);
     assert_eq!(part2, 264184041398847);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
     use test::Bencher;
 
+    const EXAMPLE: &str = "\
+190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20
+";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(3749), part1(EXAMPLE));
+        assert_eq!(Ok(11387), part2(EXAMPLE));
+    }
+
+    #[test]
+    fn test_zero_operands() {
+        assert_eq!(Ok(7), part1("7: 0 7\n0: 0 0\n"));
+        assert_eq!(Ok(50), part2("50: 5 0\n"));
+        assert!(part1("18446744073709551615: 18446744073709551615\n1: 1\n").is_err());
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, part1);
+        fuzz::assert_no_panic(EXAMPLE, part2);
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day07") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day07") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
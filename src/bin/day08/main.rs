@@ -1,99 +1,100 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 
+use advent_of_code_2024::grid::Grid;
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::ParseError;
+use advent_of_code_2024::point::Point;
 use itertools::Itertools;
-use num::Complex;
 use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 
-const N: isize = 50;
-
-fn checked_add(complex: Complex<isize>, n: isize, antinodes: &mut HashSet<Complex<isize>>) -> bool {
-    let Complex { re, im } = complex;
-
-    if re.is_negative() || im.is_negative() || re >= n || im >= n {
+fn checked_add(point: Point, (rows, cols): (usize, usize), antinodes: &mut HashSet<Point>) -> bool {
+    if !point.in_bounds(rows, cols) {
         return false;
     }
 
-    antinodes.insert(complex);
+    antinodes.insert(point);
 
     true
 }
 
-fn parse() -> HashMap<char, Vec<Complex<isize>>> {
-    let mut freqencies = HashMap::<char, Vec<Complex<isize>>>::new();
+type Frequencies = HashMap<char, Vec<Point>>;
 
-    for (x, line) in include_str!("input.txt").lines().enumerate() {
-        for (y, c) in line.chars().enumerate() {
-            if c == '.' {
-                continue;
-            }
+// Antennas by frequency, and the size of the map.
+fn parse(input: &str) -> Result<(Frequencies, (usize, usize)), ParseError> {
+    let grid = Grid::parse_with(input, "'.' or an antenna", |c| {
+        (c == '.' || c.is_ascii_alphanumeric()).then_some(c)
+    })?;
 
-            freqencies
-                .entry(c)
-                .or_default()
-                .push(Complex::new(x as isize, y as isize));
+    let mut freqencies = Frequencies::new();
+
+    for (point, &c) in grid.iter() {
+        if c != '.' {
+            freqencies.entry(c).or_default().push(point);
         }
     }
 
-    freqencies
+    Ok((freqencies, (grid.rows(), grid.cols())))
 }
 
-fn part1() -> usize {
-    let frequencies = parse();
+fn part1(input: &str) -> Result<usize, ParseError> {
+    let (frequencies, size) = parse(input)?;
 
     let mut antinodes = HashSet::new();
 
     for nodes in frequencies.values() {
         for pair in nodes.iter().combinations(2) {
-            let a1 = pair[0];
-            let a2 = pair[1];
+            let a1 = *pair[0];
+            let a2 = *pair[1];
 
             let diff = a2 - a1;
 
-            checked_add(a1 - diff, N, &mut antinodes);
-            checked_add(a2 + diff, N, &mut antinodes);
+            checked_add(a1 - diff, size, &mut antinodes);
+            checked_add(a2 + diff, size, &mut antinodes);
         }
     }
 
-    antinodes.len()
+    Ok(antinodes.len())
 }
 
-fn part2() -> usize {
-    let frequencies = parse();
+fn part2(input: &str) -> Result<usize, ParseError> {
+    let (frequencies, size) = parse(input)?;
 
     let mut antinodes = HashSet::new();
 
     for nodes in frequencies.values() {
         for pair in nodes.iter().combinations(2) {
-            let a1 = pair[0];
-            let a2 = pair[1];
+            let a1 = *pair[0];
+            let a2 = *pair[1];
 
             let diff = a2 - a1;
 
             let mut i = 0;
 
-            while checked_add(a1 - (diff * i), N, &mut antinodes) {
+            while checked_add(a1 - (diff * i), size, &mut antinodes) {
                 i += 1;
             }
 
             i = 0;
 
-            while checked_add(a2 + (diff * i), N, &mut antinodes) {
+            while checked_add(a2 + (diff * i), size, &mut antinodes) {
                 i += 1;
             }
         }
     }
 
-    antinodes.len()
+    Ok(antinodes.len())
 }
 
-pub fn main() {
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day08");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 08 ---");
@@ -104,21 +105,59 @@ pub fn main() {
 
     assert_eq!(part1, 341);
     assert_eq!(part2, 1134);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
     use test::Bencher;
 
+    const EXAMPLE: &str = "\
+............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............
+";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(14), part1(EXAMPLE));
+        assert_eq!(Ok(34), part2(EXAMPLE));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, part1);
+        fuzz::assert_no_panic(EXAMPLE, part2);
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day08") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day08") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
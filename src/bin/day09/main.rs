@@ -1,10 +1,12 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 
-use itertools::Itertools;
-use std::cmp::Ordering;
+use advent_of_code_2024::input;
+use advent_of_code_2024::interval::IntervalSet;
+use advent_of_code_2024::parse::{Input, ParseError};
+use std::ops::Range;
 use std::time::Instant;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 struct Block {
     start: usize,
     length: usize,
@@ -17,151 +19,103 @@ impl Block {
     }
 
     fn checksum(&self) -> usize {
-        self.id * (self.start * 2 + self.length - 1) * self.length / 2
+        // Sum of the positions `start..start + length`, kept from underflowing when both are zero.
+        self.id * (self.start * 2 + self.length).saturating_sub(1) * self.length / 2
     }
 }
 
-impl Eq for Block {}
+fn parse(input: &str) -> Result<(Vec<Block>, Vec<Range<usize>>), ParseError> {
+    let disk_map = input.trim_end();
 
-impl PartialEq<Self> for Block {
-    fn eq(&self, other: &Self) -> bool {
-        (self.start, self.length, self.id) == (other.start, other.length, other.id)
-    }
-}
-
-impl PartialOrd<Self> for Block {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for Block {
-    fn cmp(&self, other: &Self) -> Ordering {
-        (self.start, self.length, self.id).cmp(&(other.start, other.length, other.id))
-    }
-}
-
-struct Gap {
-    start: usize,
-    length: usize,
-}
-
-impl Gap {
-    const fn new(start: usize, length: usize) -> Self {
-        Gap { start, length }
-    }
-}
-
-fn parse() -> (Vec<Block>, Vec<Gap>) {
-    let numbers = include_bytes!("input.txt")
-        .iter()
-        .filter_map(|b| b.checked_sub(b'0').map(|i| i as usize));
+    let numbers = disk_map
+        .char_indices()
+        .map(|(i, c)| {
+            c.to_digit(10)
+                .map(|length| length as usize)
+                .ok_or_else(|| Input::new(input).error(&disk_map[i..], "a digit"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
     let mut blocks = vec![];
     let mut gaps = vec![];
     let mut pointer = 0;
 
-    for (i, length) in numbers.enumerate() {
+    for (i, length) in numbers.into_iter().enumerate() {
         if i % 2 == 0 {
             blocks.push(Block::new(pointer, length, i / 2));
         } else {
-            gaps.push(Gap::new(pointer, length));
+            gaps.push(pointer..pointer + length);
         }
 
         pointer += length;
     }
 
-    gaps.reverse();
-
-    (blocks, gaps)
+    Ok((blocks, gaps))
 }
 
-fn calculate_checksum(blocks: &[Block], moved_blocks: &[Block]) -> usize {
-    blocks
-        .iter()
-        .chain(moved_blocks.iter())
-        .sorted_unstable()
-        .map(Block::checksum)
-        .sum::<usize>()
-}
+// Fills the gaps from the left with blocks from the right, splitting blocks and gaps as needed.
+fn part1(input: &str) -> Result<usize, ParseError> {
+    let (mut blocks, mut gaps) = parse(input)?;
 
-fn part1() -> usize {
-    let (mut blocks, mut gaps) = parse();
+    gaps.reverse();
 
-    let mut moved_blocks = vec![];
+    let mut checksum = 0;
 
     while let Some(mut gap) = gaps.pop() {
-        let block = blocks.last_mut().unwrap();
+        let Some(block) = blocks.last_mut() else {
+            break;
+        };
 
         if gap.start >= block.start {
             break;
         }
 
-        if block.length > gap.length {
-            moved_blocks.push(Block::new(gap.start, gap.length, block.id));
-            block.length -= gap.length;
+        if block.length > gap.len() {
+            checksum += Block::new(gap.start, gap.len(), block.id).checksum();
+            block.length -= gap.len();
             continue;
         }
 
-        moved_blocks.push(Block::new(gap.start, block.length, block.id));
+        checksum += Block::new(gap.start, block.length, block.id).checksum();
 
-        if block.length < gap.length {
+        if block.length < gap.len() {
             gap.start += block.length;
-            gap.length -= block.length;
-
             gaps.push(gap);
         }
 
         blocks.pop();
     }
 
-    calculate_checksum(&blocks, &moved_blocks)
+    Ok(checksum + blocks.iter().map(Block::checksum).sum::<usize>())
 }
 
-fn part2() -> usize {
-    let (mut blocks, mut gaps) = parse();
+fn part2(input: &str) -> Result<usize, ParseError> {
+    let (blocks, gaps) = parse(input)?;
+    let mut gaps = IntervalSet::from_iter(gaps);
 
-    let mut moved_blocks = vec![];
-
-    while let Some(mut gap) = gaps.pop() {
-        let mut i = blocks.len() - 1;
-
-        let mut block = blocks.get_mut(i).unwrap();
-
-        while gap.start < block.start {
-            if block.length <= gap.length {
-                break;
-            }
+    let mut checksum = 0;
 
-            i -= 1;
-            block = blocks.get_mut(i).unwrap();
+    for mut block in blocks.into_iter().rev() {
+        if let Some(start) = gaps.first_fit(block.length, block.start) {
+            gaps.remove(start..start + block.length);
+            block.start = start;
         }
 
-        if gap.start < block.start {
-            block.start = gap.start;
-            moved_blocks.push(*block);
-
-            if block.length < gap.length {
-                gap.start += block.length;
-                gap.length -= block.length;
-
-                gaps.push(gap);
-            }
-
-            blocks.remove(i);
-        }
+        checksum += block.checksum();
     }
 
-    calculate_checksum(&blocks, &moved_blocks)
+    Ok(checksum)
 }
 
-pub fn main() {
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day09");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 09 ---");
@@ -172,21 +126,139 @@ pub fn main() {
 
     assert_eq!(part1, 6334655979668);
     assert_eq!(part2, 6349492251099);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
+    use proptest::prelude::*;
     use test::Bencher;
 
+    const EXAMPLE: &str = "2333133121414131402\n";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(1928), part1(EXAMPLE));
+        assert_eq!(Ok(2858), part2(EXAMPLE));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, part1);
+        fuzz::assert_no_panic(EXAMPLE, part2);
+    }
+
+    // One entry per block on the disk, holding its file id if it is not free.
+    fn expand(input: &str) -> Vec<Option<usize>> {
+        input
+            .trim()
+            .bytes()
+            .enumerate()
+            .flat_map(|(i, b)| {
+                let file = (i % 2 == 0).then_some(i / 2);
+                std::iter::repeat_n(file, (b - b'0') as usize)
+            })
+            .collect()
+    }
+
+    fn checksum(disk: &[Option<usize>]) -> usize {
+        disk.iter()
+            .enumerate()
+            .filter_map(|(pos, file)| Some(pos * (*file)?))
+            .sum()
+    }
+
+    fn part1_oracle(input: &str) -> usize {
+        let mut disk = expand(input);
+        let mut left = 0;
+        let mut right = disk.len();
+
+        loop {
+            while left < disk.len() && disk[left].is_some() {
+                left += 1;
+            }
+
+            while right > 0 && disk[right - 1].is_none() {
+                right -= 1;
+            }
+
+            if right == 0 || left >= right - 1 {
+                break;
+            }
+
+            disk.swap(left, right - 1);
+        }
+
+        checksum(&disk)
+    }
+
+    fn part2_oracle(input: &str) -> usize {
+        let mut disk = expand(input);
+        let files = disk.iter().flatten().max().map_or(0, |&id| id + 1);
+
+        for id in (0..files).rev() {
+            let start = disk.iter().position(|&file| file == Some(id)).unwrap();
+            let length = disk.iter().filter(|&&file| file == Some(id)).count();
+
+            let Some(gap) =
+                (0..start).find(|&gap| disk[gap..gap + length].iter().all(Option::is_none))
+            else {
+                continue;
+            };
+
+            for i in 0..length {
+                disk.swap(gap + i, start + i);
+            }
+        }
+
+        checksum(&disk)
+    }
+
+    // Alternating file and gap lengths. Files are never empty, gaps may be.
+    fn disk_maps() -> impl Strategy<Value = String> {
+        prop::collection::vec((1..=9u8, 0..=9u8), 1..20).prop_map(|entries| {
+            let mut map = entries
+                .iter()
+                .flat_map(|&(file, gap)| [b'0' + file, b'0' + gap])
+                .map(char::from)
+                .collect::<String>();
+
+            map.pop();
+            map + "\n"
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_part1_matches_oracle(input in disk_maps()) {
+            prop_assert_eq!(Ok(part1_oracle(&input)), part1(&input));
+        }
+
+        #[test]
+        fn test_part2_matches_oracle(input in disk_maps()) {
+            prop_assert_eq!(Ok(part2_oracle(&input)), part2(&input));
+        }
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day09") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day09") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
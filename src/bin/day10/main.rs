@@ -1,111 +1,100 @@
-#![feature(test)]
-
-use std::collections::{HashMap, HashSet, VecDeque};
+#![cfg_attr(test, feature(test))]
+
+use advent_of_code_2024::grid::Grid;
+use advent_of_code_2024::input;
+use advent_of_code_2024::memo::{DenseCache, Memo};
+use advent_of_code_2024::parse::ParseError;
+use advent_of_code_2024::point::Point;
+use std::collections::{HashSet, VecDeque};
 use std::time::Instant;
 
-type Grid = [[u8; N]; N];
-type Point = (usize, usize);
-
-const N: usize = 53;
+// Heights, with `None` for the impassable `.` tiles of the examples.
+fn parse(input: &str) -> Result<(Grid<Option<u8>>, Vec<Point>), ParseError> {
+    let grid = Grid::parse_with(input, "a digit or '.'", |c| match c {
+        '.' => Some(None),
+        _ => c.to_digit(10).map(|digit| Some(digit as u8)),
+    })?;
 
-fn parse() -> (Grid, Vec<Point>) {
-    let mut grid = [[0u8; N]; N];
+    let heads = grid
+        .iter()
+        .filter(|&(_, &digit)| digit == Some(0))
+        .map(|(point, _)| point)
+        .collect();
 
-    let mut heads = vec![];
+    Ok((grid, heads))
+}
 
-    for (x, line) in include_str!("input.txt").lines().enumerate() {
-        for (y, c) in line.chars().enumerate() {
-            let digit = c.to_digit(10).unwrap() as u8;
-            grid[x][y] = digit;
+fn height(grid: &Grid<Option<u8>>, point: Point) -> Option<u8> {
+    grid.get(point).copied().flatten()
+}
 
-            if digit == 0 {
-                heads.push((x, y));
-            }
-        }
-    }
+fn uphill_neighbours(grid: &Grid<Option<u8>>, point: Point) -> impl Iterator<Item = Point> + '_ {
+    let curr = height(grid, point);
 
-    (grid, heads)
+    point.neighbours().into_iter().filter(move |&neighbour| {
+        height(grid, neighbour)
+            .zip(curr)
+            .and_then(|(h, c)| h.checked_sub(c))
+            == Some(1)
+    })
 }
 
-fn bfs(grid: &Grid, start: Point) -> usize {
+fn bfs(grid: &Grid<Option<u8>>, start: Point) -> usize {
     let mut queue = VecDeque::from([start]);
     let mut dest = HashSet::new();
 
-    while let Some((x, y)) = queue.pop_front() {
-        if grid[x][y] == 9 {
-            dest.insert((x, y));
+    while let Some(point) = queue.pop_front() {
+        if height(grid, point) == Some(9) {
+            dest.insert(point);
             continue;
         }
 
-        for (dx, dy) in [(-1, 0), (0, 1), (1, 0), (0, -1)] {
-            let Some((xx, yy)) = x.checked_add_signed(dx).zip(y.checked_add_signed(dy)) else {
-                continue;
-            };
-
-            if xx >= grid.len() || yy >= grid.len() {
-                continue;
-            }
-
-            if grid[xx][yy].checked_sub(grid[x][y]) == Some(1) {
-                queue.push_back((xx, yy));
-            }
-        }
+        queue.extend(uphill_neighbours(grid, point));
     }
 
     dest.len()
 }
 
-fn part1() -> usize {
-    let (grid, heads) = parse();
-    heads.iter().map(|point| bfs(&grid, *point)).sum()
+fn part1(input: &str) -> Result<usize, ParseError> {
+    let (grid, heads) = parse(input)?;
+    Ok(heads.iter().map(|point| bfs(&grid, *point)).sum())
 }
 
-fn dfs(grid: &Grid, (x, y): Point, map: &mut HashMap<Point, u32>) -> u32 {
-    if let Some(score) = map.get(&(x, y)) {
-        return *score;
-    }
-
-    if grid[x][y] == 9 {
-        map.insert((x, y), 1);
-        return 1;
-    }
-
-    let mut score = 0;
-
-    for (dx, dy) in [(-1, 0), (0, 1), (1, 0), (0, -1)] {
-        let Some((xx, yy)) = x.checked_add_signed(dx).zip(y.checked_add_signed(dy)) else {
-            continue;
-        };
+fn part2(input: &str) -> Result<u32, ParseError> {
+    let (grid, heads) = parse(input)?;
+    let (rows, cols) = (grid.rows(), grid.cols());
 
-        if xx >= grid.len() || yy >= grid.len() {
-            continue;
-        }
+    // Keyed by flat index so that the cache can stay dense.
+    let mut ratings = Memo::with_cache(
+        DenseCache::new(rows * cols, |&index| index),
+        |rating, index| {
+            let point = Point::from_index(index, cols);
 
-        if grid[xx][yy].checked_sub(grid[x][y]) == Some(1) {
-            score += dfs(grid, (xx, yy), map);
-        }
-    }
+            if height(&grid, point) == Some(9) {
+                return 1;
+            }
 
-    map.insert((x, y), score);
+            uphill_neighbours(&grid, point)
+                .map(|neighbour| rating(neighbour.to_index(rows, cols).unwrap()))
+                .sum()
+        },
+    );
 
-    score
+    Ok(heads
+        .iter()
+        .map(|&point| ratings.get(point.to_index(rows, cols).unwrap()))
+        .sum())
 }
 
-fn part2() -> u32 {
-    let (grid, heads) = parse();
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day10");
 
-    let mut map = HashMap::new();
-
-    heads.iter().map(|point| dfs(&grid, *point, &mut map)).sum()
-}
-
-pub fn main() {
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 10 ---");
@@ -116,21 +105,81 @@ pub fn main() {
 
     assert_eq!(part1, 776);
     assert_eq!(part2, 1657);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
     use test::Bencher;
 
+    const EXAMPLE: &str = "\
+89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732
+";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(36), part1(EXAMPLE));
+        assert_eq!(Ok(81), part2(EXAMPLE));
+    }
+
+    #[test]
+    fn test_impassable_tiles() {
+        let scored = "\
+..90..9
+...1.98
+...2..7
+6543456
+765.987
+876....
+987....
+";
+
+        let rated = "\
+.....0.
+..4321.
+..5..2.
+..6543.
+..7..4.
+..8765.
+..9....
+";
+
+        assert_eq!(Ok(4), part1(scored));
+        assert_eq!(Ok(3), part2(rated));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, part1);
+        fuzz::assert_no_panic(EXAMPLE, part2);
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day10") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day10") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
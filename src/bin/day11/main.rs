@@ -1,23 +1,24 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 
-use num::Integer;
+use advent_of_code_2024::input;
+use advent_of_code_2024::number;
+use advent_of_code_2024::parse::{Input, ParseError};
 use std::collections::HashMap;
 use std::mem::swap;
 use std::time::Instant;
 
-fn digits(i: u64) -> u32 {
-    i.checked_ilog10().unwrap_or(0) + 1
-}
-
 fn update(state: &mut HashMap<u64, u64>, i: u64, count: u64) {
     *state.entry(i).or_default() += count;
 }
 
-fn simulate(part1: bool) -> u64 {
-    let mut state: HashMap<u64, u64> = include_str!("input.txt")
-        .split_ascii_whitespace()
-        .filter_map(|s| Some((s.parse::<u64>().ok()?, 1)))
-        .collect();
+fn simulate(input: &str, part1: bool) -> Result<u64, ParseError> {
+    let input = Input::new(input);
+
+    let mut state = HashMap::new();
+
+    for s in input.as_str().split_ascii_whitespace() {
+        update(&mut state, input.parse(s)?, 1);
+    }
 
     let mut new_state = HashMap::new();
 
@@ -28,39 +29,46 @@ fn simulate(part1: bool) -> u64 {
                 continue;
             }
 
-            let digits = digits(i).div_rem(&2);
+            let digits = number::digit_count(i, 10);
 
-            if digits.1 == 0 {
-                let (div, rem) = i.div_rem(&(10u64.pow(digits.0)));
+            if digits.is_multiple_of(2) {
+                let (div, rem) = number::split_digits(i, digits / 2, 10);
                 update(&mut new_state, div, count);
                 update(&mut new_state, rem, count);
                 continue;
             }
 
-            update(&mut new_state, i * 2024, count);
+            let Some(i) = i.checked_mul(2024) else {
+                return Err(input.error(input.as_str(), "stones that stay within a u64"));
+            };
+
+            update(&mut new_state, i, count);
         }
 
         swap(&mut state, &mut new_state);
         new_state.clear();
     }
 
-    state.values().sum()
+    Ok(state.values().sum())
 }
 
-fn part1() -> u64 {
-    simulate(true)
+fn part1(input: &str) -> Result<u64, ParseError> {
+    simulate(input, true)
 }
-fn part2() -> u64 {
-    simulate(false)
+
+fn part2(input: &str) -> Result<u64, ParseError> {
+    simulate(input, false)
 }
 
-pub fn main() {
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day11");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 11 ---");
@@ -71,21 +79,51 @@ pub fn main() {
 
     assert_eq!(part1, 185205);
     assert_eq!(part2, 221280540398419);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
     use test::Bencher;
 
+    const EXAMPLE: &str = "125 17\n";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(55312), part1(EXAMPLE));
+        assert_eq!(Ok(65601038650482), part2(EXAMPLE));
+    }
+
+    #[test]
+    fn test_overflowing_stone() {
+        assert!(part1("1234567890123456789\n").is_err());
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, part1);
+        fuzz::assert_no_panic(EXAMPLE, part2);
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day11") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day11") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
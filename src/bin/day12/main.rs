@@ -1,125 +1,94 @@
-#![feature(test)]
-
-use itertools::Itertools;
-use num::Complex;
-use std::collections::{HashMap, HashSet, VecDeque};
+#![cfg_attr(test, feature(test))]
+
+use advent_of_code_2024::bitset::BitGrid;
+use advent_of_code_2024::grid::Grid;
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::ParseError;
+use advent_of_code_2024::point::{Direction, Point};
+use std::collections::VecDeque;
 use std::time::Instant;
 
-const N: isize = 140;
-
-fn parse() -> HashMap<Complex<isize>, char> {
-    let mut grid = HashMap::new();
-
-    for (x, line) in include_str!("input.txt").lines().enumerate() {
-        for (y, c) in line.chars().enumerate() {
-            grid.insert(Complex::new(x as isize, y as isize), c);
-        }
-    }
-
-    grid
-}
-
-fn flood(
-    grid: &HashMap<Complex<isize>, char>,
-    origin: Complex<isize>,
-    visited: &mut HashSet<Complex<isize>>,
-) -> HashSet<Complex<isize>> {
-    let mut region = HashSet::new();
+fn flood(grid: &Grid<char>, origin: Point, visited: &mut BitGrid) -> BitGrid {
+    let mut region = BitGrid::new(grid.rows(), grid.cols());
 
     let mut queue = VecDeque::from([origin]);
 
     while let Some(pos) = queue.pop_front() {
-        if visited.contains(&pos) {
+        if !visited.insert(pos) {
             continue;
         }
 
-        visited.insert(pos);
         region.insert(pos);
 
-        let plot = grid.get(&pos).unwrap();
-
-        let mut direction = -Complex::ONE;
+        let plot = grid[pos];
 
-        for _ in 0..4 {
-            let neighbour_pos = pos + direction;
-
-            if grid
-                .get(&neighbour_pos)
-                .is_some_and(|neighbour| plot == neighbour)
-            {
+        for neighbour_pos in pos.neighbours() {
+            if grid.get(neighbour_pos) == Some(&plot) {
                 queue.push_back(neighbour_pos);
             }
-
-            direction *= -Complex::I;
         }
     }
 
     region
 }
 
-fn calculate_perimeter(region: &HashSet<Complex<isize>>, part1: bool) -> usize {
+fn calculate_perimeter(region: &BitGrid, part1: bool) -> usize {
+    let up = Direction::Up.offset();
+    let right = Direction::Right.offset();
+    let down = Direction::Down.offset();
+    let left = Direction::Left.offset();
+
     let mut perimeter = 0;
 
-    for pos in region
-        .iter()
-        .sorted_unstable_by(|p1, p2| (p1.re, p1.im).cmp(&(p2.re, p2.im)))
-    {
+    for pos in region.iter() {
         // Check up
-        let mut neighbour_pos = pos - Complex::ONE;
+        let mut neighbour_pos = pos + up;
 
-        if pos.re == 0 {
-            if part1 || !region.contains(&(pos - Complex::I)) {
+        if pos.x == 0 {
+            if part1 || !region.contains(pos + left) {
                 perimeter += 1;
             }
-        } else if !region.contains(&neighbour_pos)
-            && (part1
-                || !region.contains(&(pos - Complex::I))
-                || region.contains(&(pos + Complex::new(-1, -1))))
+        } else if !region.contains(neighbour_pos)
+            && (part1 || !region.contains(pos + left) || region.contains(pos + up + left))
         {
             perimeter += 1;
         }
 
         // Check right
-        neighbour_pos = pos + Complex::I;
+        neighbour_pos = pos + right;
 
-        if pos.im == N - 1 {
-            if part1 || !region.contains(&(pos - Complex::ONE)) {
+        if pos.y == region.cols() as isize - 1 {
+            if part1 || !region.contains(pos + up) {
                 perimeter += 1;
             }
-        } else if !region.contains(&neighbour_pos)
-            && (part1
-                || !region.contains(&(pos - Complex::ONE))
-                || region.contains(&(pos + Complex::new(-1, 1))))
+        } else if !region.contains(neighbour_pos)
+            && (part1 || !region.contains(pos + up) || region.contains(pos + up + right))
         {
             perimeter += 1;
         }
 
         // Check down
-        neighbour_pos = pos + Complex::ONE;
+        neighbour_pos = pos + down;
 
-        if pos.re == N - 1 {
-            if part1 || !region.contains(&(pos - Complex::I)) {
+        if pos.x == region.rows() as isize - 1 {
+            if part1 || !region.contains(pos + left) {
                 perimeter += 1;
             }
-        } else if !region.contains(&neighbour_pos)
-            && (part1
-                || !region.contains(&(pos - Complex::I))
-                || region.contains(&(pos + Complex::new(1, -1))))
+        } else if !region.contains(neighbour_pos)
+            && (part1 || !region.contains(pos + left) || region.contains(pos + down + left))
         {
             perimeter += 1;
         }
 
         // Check left
-        neighbour_pos = pos - Complex::I;
+        neighbour_pos = pos + left;
 
-        if pos.im == 0 {
-            if part1 || !region.contains(&(pos - Complex::ONE)) {
+        if pos.y == 0 {
+            if part1 || !region.contains(pos + up) {
                 perimeter += 1;
             }
-        } else if !region.contains(&neighbour_pos)
-            && (part1
-                || !region.contains(&(pos - Complex::ONE))
-                || region.contains(&(pos + Complex::new(-1, -1))))
+        } else if !region.contains(neighbour_pos)
+            && (part1 || !region.contains(pos + up) || region.contains(pos + up + left))
         {
             perimeter += 1;
         }
@@ -128,47 +97,45 @@ fn calculate_perimeter(region: &HashSet<Complex<isize>>, part1: bool) -> usize {
     perimeter
 }
 
-fn calculate(part1: bool) -> usize {
-    let grid = parse();
+fn calculate(input: &str, part1: bool) -> Result<usize, ParseError> {
+    let grid = Grid::parse(input)?;
 
     let mut ans = 0;
 
-    let mut visited = HashSet::new();
+    let mut visited = BitGrid::new(grid.rows(), grid.cols());
 
-    for x in 0..N {
-        for y in 0..N {
-            let pos = Complex::new(x, y);
-
-            if visited.contains(&pos) {
-                continue;
-            }
+    for pos in grid.points() {
+        if visited.contains(pos) {
+            continue;
+        }
 
-            let region = flood(&grid, pos, &mut visited);
+        let region = flood(&grid, pos, &mut visited);
 
-            let perimeter = calculate_perimeter(&region, part1);
+        let perimeter = calculate_perimeter(&region, part1);
 
-            ans += region.len() * perimeter;
-        }
+        ans += region.len() * perimeter;
     }
 
-    ans
+    Ok(ans)
 }
 
-fn part1() -> usize {
-    calculate(true)
+fn part1(input: &str) -> Result<usize, ParseError> {
+    calculate(input, true)
 }
 
-fn part2() -> usize {
-    calculate(false)
+fn part2(input: &str) -> Result<usize, ParseError> {
+    calculate(input, false)
 }
 
-pub fn main() {
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day12");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 12 ---");
@@ -179,21 +146,154 @@ pub fn main() {
 
     assert_eq!(part1, 1396562);
     assert_eq!(part2, 844132);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
+    use proptest::prelude::*;
+    use std::collections::{HashMap, HashSet};
     use test::Bencher;
 
+    const SMALL_EXAMPLE: &str = "\
+AAAA
+BBCD
+BBCC
+EEEC
+";
+
+    const LARGE_EXAMPLE: &str = "\
+RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE
+";
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(Ok(140), part1(SMALL_EXAMPLE));
+        assert_eq!(Ok(80), part2(SMALL_EXAMPLE));
+        assert_eq!(Ok(1930), part1(LARGE_EXAMPLE));
+        assert_eq!(Ok(1206), part2(LARGE_EXAMPLE));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(LARGE_EXAMPLE, part1);
+        fuzz::assert_no_panic(LARGE_EXAMPLE, part2);
+    }
+
+    // Every region found by a plain flood fill, with its perimeter and number of sides.
+    fn regions_oracle(input: &str) -> Vec<(usize, usize, usize)> {
+        let plots = input
+            .lines()
+            .enumerate()
+            .flat_map(|(x, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(move |(y, c)| (Point::new(x as isize, y as isize), c))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut seen = HashSet::new();
+        let mut regions = vec![];
+
+        for (&origin, &plant) in &plots {
+            if seen.contains(&origin) {
+                continue;
+            }
+
+            let mut region = HashSet::new();
+            let mut stack = vec![origin];
+
+            while let Some(pos) = stack.pop() {
+                if plots.get(&pos) == Some(&plant) && region.insert(pos) {
+                    stack.extend(pos.neighbours());
+                }
+            }
+
+            // A fence runs along the `dir` side of `pos`.
+            let fences = region
+                .iter()
+                .flat_map(|&pos| Direction::ALL.map(|dir| (pos, dir)))
+                .filter(|&(pos, dir)| !region.contains(&(pos + dir)))
+                .collect::<HashSet<_>>();
+
+            // Each side is counted at the fence with no fence before it along the same line.
+            let sides = fences
+                .iter()
+                .filter(|&&(pos, dir)| !fences.contains(&(pos + dir.turn_left(), dir)))
+                .count();
+
+            seen.extend(region.iter().copied());
+            regions.push((region.len(), fences.len(), sides));
+        }
+
+        regions
+    }
+
+    fn gardens() -> impl Strategy<Value = String> {
+        (1..8usize, 1..8usize)
+            .prop_flat_map(|(rows, cols)| {
+                prop::collection::vec(
+                    prop::collection::vec(prop::char::range('A', 'C'), cols),
+                    rows,
+                )
+            })
+            .prop_map(|rows| {
+                rows.iter()
+                    .map(|row| row.iter().collect::<String>() + "\n")
+                    .collect()
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn test_part1_matches_oracle(input in gardens()) {
+            let expected = regions_oracle(&input)
+                .iter()
+                .map(|&(area, perimeter, _)| area * perimeter)
+                .sum::<usize>();
+
+            prop_assert_eq!(Ok(expected), part1(&input));
+        }
+
+        #[test]
+        fn test_part2_matches_oracle(input in gardens()) {
+            let expected = regions_oracle(&input)
+                .iter()
+                .map(|&(area, _, sides)| area * sides)
+                .sum::<usize>();
+
+            prop_assert_eq!(Ok(expected), part2(&input));
+        }
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day12") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day12") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
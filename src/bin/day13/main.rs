@@ -1,66 +1,115 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 
-use itertools::Itertools;
-use num::Integer;
-use regex::Regex;
+use advent_of_code_2024::input;
+use advent_of_code_2024::linalg::{self, Solution};
+use advent_of_code_2024::parse::{Input, ParseError};
+use num::{BigInt, Signed, ToPrimitive, Zero};
 use std::time::Instant;
 
-fn solve(part1: bool) -> i64 {
-    let file = include_str!("input.txt");
+fn parse(input: &str) -> Result<Vec<[i64; 6]>, ParseError> {
+    let input = Input::new(input);
 
-    let re = Regex::new(r"\d+").unwrap();
-
-    let mut ans = 0;
+    input
+        .sections()
+        .into_iter()
+        .map(|section| input.integers_n(section))
+        .collect()
+}
 
-    for chunk in &re
-        .captures_iter(file)
-        .filter_map(|caps| caps.get(0).and_then(|m| m.as_str().parse::<i64>().ok()))
-        .chunks(6)
-    {
-        let mut numbers = chunk.collect_tuple::<(_, _, _, _, _, _)>().unwrap();
+// Fewest tokens to win, where A costs 3 and B costs 1. Elimination multiplies entries together,
+// which would overflow an `i64` for part 2's prizes.
+fn tokens([ax, ay, bx, by, x, y]: &[BigInt; 6]) -> Option<BigInt> {
+    let solution = linalg::solve(
+        &[vec![ax.clone(), bx.clone()], vec![ay.clone(), by.clone()]],
+        &[x.clone(), y.clone()],
+    );
 
-        // (ax, ay, bx, by, x, y)
-        // ax * a_div + bx * b_div = x
-        // ay * a_div + by * b_div = y
+    match solution {
+        Solution::Unique(_) => {
+            let presses = solution.integer()?;
 
-        if !part1 {
-            numbers.4 += 10000000000000;
-            numbers.5 += 10000000000000;
+            presses
+                .iter()
+                .all(|n| !n.is_negative())
+                .then(|| &presses[0] * 3 + &presses[1])
         }
+        // Both buttons move the claw along the same line, so one axis decides everything and the
+        // cost is linear in k: take whichever end of the feasible range is cheaper.
+        Solution::Underdetermined { .. } => {
+            let (a, b, c) = if ax.is_zero() && bx.is_zero() {
+                (ay, by, y)
+            } else {
+                (ax, bx, x)
+            };
+
+            match (a.is_zero(), b.is_zero()) {
+                // Neither button moves the claw, so it must already be on the prize.
+                (true, true) => return Some(BigInt::zero()),
+                // Only one button moves the claw, so the other is never worth pressing.
+                (true, false) => return presses_along(c, b),
+                (false, true) => return presses_along(c, a).map(|presses| presses * 3),
+                _ => {}
+            }
 
-        let ax = numbers.0 * numbers.1;
-        let _ay = numbers.1 * numbers.0;
-        let bx = numbers.2 * numbers.1;
-        let by = numbers.3 * numbers.0;
-        let x = numbers.4 * numbers.1;
-        let y = numbers.5 * numbers.0;
+            if a.is_negative() || b.is_negative() {
+                return None;
+            }
 
-        let (b_div, b_rem) = (x - y).div_rem(&(bx - by));
-        let (a_div, a_rem) = (x - (bx * b_div)).div_rem(&ax);
+            let presses = linalg::diophantine(a.clone(), b.clone(), c.clone())?;
+            let (low, high) = presses.non_negative()?;
+            let k = if &presses.dx * 3 > presses.dy {
+                low
+            } else {
+                high
+            };
+            let (a, b) = presses.at(k);
 
-        if a_rem == 0 && b_rem == 0 {
-            ans += a_div * 3 + b_div;
+            Some(a * 3 + b)
         }
+        Solution::Inconsistent => None,
     }
+}
+
+// How many presses of a button moving `step` along an axis reach `c`, if any.
+fn presses_along(c: &BigInt, step: &BigInt) -> Option<BigInt> {
+    let presses = c / step;
 
-    ans
+    (&presses * step == *c && !presses.is_negative()).then_some(presses)
 }
 
-fn part1() -> i64 {
-    solve(true)
+fn solve(input: &str, part1: bool) -> Result<i64, ParseError> {
+    let offset = BigInt::from(if part1 { 0 } else { 10000000000000i64 });
+
+    let tokens = parse(input)?
+        .into_iter()
+        .filter_map(|machine| {
+            let [ax, ay, bx, by, x, y] = machine.map(BigInt::from);
+            tokens(&[ax, ay, bx, by, x + &offset, y + &offset])
+        })
+        .sum::<BigInt>();
+
+    tokens
+        .to_i64()
+        .ok_or_else(|| Input::new(input).error(input, "a token count that fits in an i64"))
 }
 
-fn part2() -> i64 {
-    solve(false)
+fn part1(input: &str) -> Result<i64, ParseError> {
+    solve(input, true)
 }
 
-pub fn main() {
+fn part2(input: &str) -> Result<i64, ParseError> {
+    solve(input, false)
+}
+
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day13");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 13 ---");
@@ -71,21 +120,139 @@ pub fn main() {
 
     assert_eq!(part1, 26299);
     assert_eq!(part2, 107824497933339);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
+    use itertools::Itertools;
+    use proptest::prelude::*;
     use test::Bencher;
 
+    const EXAMPLE: &str = "\
+Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+26, Y+66
+Button B: X+67, Y+21
+Prize: X=12748, Y=12176
+
+Button A: X+17, Y+86
+Button B: X+84, Y+37
+Prize: X=7870, Y=6450
+
+Button A: X+69, Y+23
+Button B: X+27, Y+71
+Prize: X=18641, Y=10279
+";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(480), part1(EXAMPLE));
+        assert_eq!(Ok(875318608908), part2(EXAMPLE));
+    }
+
+    #[test]
+    fn test_still_buttons() {
+        let machine = |[ax, ay, bx, by, x, y]: [i64; 6]| {
+            format!("Button A: X+{ax}, Y+{ay}\nButton B: X+{bx}, Y+{by}\nPrize: X={x}, Y={y}\n")
+        };
+
+        assert_eq!(Ok(2), part1(&machine([0, 0, 2, 3, 4, 6])));
+        assert_eq!(Ok(6), part1(&machine([2, 3, 0, 0, 4, 6])));
+        assert_eq!(Ok(0), part1(&machine([2, 3, 0, 0, 3, 6])));
+        assert_eq!(Ok(0), part1(&machine([0, 0, 0, 0, 0, 0])));
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        let input = "\
+Button A: X+3000000007, Y+2999999999
+Button B: X+2000000011, Y+3000000019
+Prize: X=2000000047000, Y=5000000055000
+";
+        assert_eq!(Ok(9000), part2(input));
+
+        let input = "\
+Button A: X+1, Y+0
+Button B: X+0, Y+1
+Prize: X=9223372036854775807, Y=9223372036854775807
+";
+        assert!(part1(input).is_err());
+        assert!(part2(input).is_err());
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, part1);
+        fuzz::assert_no_panic(EXAMPLE, part2);
+    }
+
+    // Tries every number of presses up to `PRESSES`, enough to reach any prize a button that moves
+    // at all can.
+    fn tokens_oracle([ax, ay, bx, by, x, y]: [i64; 6]) -> Option<i64> {
+        const PRESSES: i64 = 150;
+
+        (0..=PRESSES)
+            .cartesian_product(0..=PRESSES)
+            .filter(|&(a, b)| a * ax + b * bx == x && a * ay + b * by == y)
+            .map(|(a, b)| a * 3 + b)
+            .min()
+    }
+
+    // Small button moves make parallel buttons common, and some buttons move along only one axis
+    // or not at all. Half of the prizes are reachable by construction, the rest are arbitrary.
+    fn machines() -> impl Strategy<Value = Vec<[i64; 6]>> {
+        let machine = (0..6i64, 0..6i64, 0..6i64, 0..6i64).prop_flat_map(|(ax, ay, bx, by)| {
+            prop_oneof![
+                (0..30i64, 0..30i64).prop_map(move |(a, b)| (a * ax + b * bx, a * ay + b * by)),
+                (0..150i64, 0..150i64),
+            ]
+            .prop_map(move |(x, y)| [ax, ay, bx, by, x, y])
+        });
+
+        prop::collection::vec(machine, 1..10)
+    }
+
+    proptest! {
+        #[test]
+        fn test_part1_matches_oracle(machines in machines()) {
+            let input = machines
+                .iter()
+                .map(|[ax, ay, bx, by, x, y]| {
+                    format!(
+                        "Button A: X+{ax}, Y+{ay}\nButton B: X+{bx}, Y+{by}\nPrize: X={x}, Y={y}\n"
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            let expected = machines.iter().filter_map(|&machine| tokens_oracle(machine)).sum();
+
+            prop_assert_eq!(Ok(expected), part1(&input));
+        }
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day13") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day13") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
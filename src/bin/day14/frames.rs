@@ -0,0 +1,178 @@
+use crate::Robot;
+use advent_of_code_2024::bitset::BitGrid;
+use advent_of_code_2024::grid::Grid;
+use advent_of_code_2024::point::Point;
+use advent_of_code_2024::render::{Canvas, Colour, Style};
+use itertools::Itertools;
+
+// The side of the squares the space is divided into when measuring entropy: about the size of a
+// branch of the tree, so that a picture fills a few squares and noise spreads over all of them.
+const BLOCK: usize = 8;
+
+/// Where each robot is after `seconds`, as a point with a row for each `y` and a column for each
+/// `x`.
+pub fn positions(robots: &[Robot], seconds: i64, width: i64, height: i64) -> Vec<Point> {
+    robots
+        .iter()
+        .map(|robot| {
+            let (x, y) = robot.position(seconds, width, height);
+            Point::new(y as isize, x as isize)
+        })
+        .collect()
+}
+
+/// How many robots are in each tile after `seconds`.
+pub fn frame(robots: &[Robot], seconds: i64, width: i64, height: i64) -> Grid<usize> {
+    let mut frame = Grid::new(height as usize, width as usize, 0);
+
+    for point in positions(robots, seconds, width, height) {
+        frame[point] += 1;
+    }
+
+    frame
+}
+
+/// Robots in green, counted up to 9, on black.
+pub fn canvas(frame: &Grid<usize>) -> Canvas {
+    Canvas::new(frame, |_, &count| match count {
+        0 => Style::new('.', Colour::BLACK),
+        1..=9 => Style::new(char::from(b'0' + count as u8), Colour::GREEN),
+        _ => Style::new('+', Colour::GREEN),
+    })
+}
+
+/// Ways of telling how far a frame is from showing a picture. Lower scores are more orderly.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Score {
+    /// The Shannon entropy, in bits, of how the robots spread over squares of the space.
+    Entropy,
+    /// The fraction of robots with no robot in any of the eight tiles around them.
+    Clustering,
+}
+
+impl Score {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "entropy" => Some(Self::Entropy),
+            "clustering" => Some(Self::Clustering),
+            _ => None,
+        }
+    }
+
+    /// Scores the robots at `positions` in a `width` by `height` space.
+    pub fn score(self, positions: &[Point], width: i64, height: i64) -> f64 {
+        let (rows, cols) = (height as usize, width as usize);
+        let robots = positions.len() as f64;
+
+        match self {
+            Self::Entropy => {
+                let blocks_across = cols.div_ceil(BLOCK);
+                let mut blocks = vec![0; rows.div_ceil(BLOCK) * blocks_across];
+
+                for point in positions {
+                    blocks[point.x as usize / BLOCK * blocks_across + point.y as usize / BLOCK] +=
+                        1;
+                }
+
+                blocks
+                    .into_iter()
+                    .filter(|&count| count > 0)
+                    .map(|count| {
+                        let p = count as f64 / robots;
+                        -p * p.log2()
+                    })
+                    .sum()
+            }
+            Self::Clustering => {
+                let mut occupied = BitGrid::new(rows, cols);
+
+                for &point in positions {
+                    occupied.insert(point);
+                }
+
+                let alone = positions
+                    .iter()
+                    .filter(|&&point| {
+                        Point::ADJACENT
+                            .iter()
+                            .all(|&offset| !occupied.contains(point + offset))
+                    })
+                    .count();
+
+                alone as f64 / robots
+            }
+        }
+    }
+}
+
+/// The `count` most orderly seconds before the robots start repeating themselves, best first,
+/// with their scores.
+pub fn rank(
+    robots: &[Robot],
+    score: Score,
+    count: usize,
+    width: i64,
+    height: i64,
+) -> Vec<(i64, f64)> {
+    (0..width * height)
+        .map(|seconds| {
+            let positions = positions(robots, seconds, width, height);
+            (seconds, score.score(&positions, width, height))
+        })
+        .sorted_by(|(_, a), (_, b)| a.total_cmp(b))
+        .take(count)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, part2, HEIGHT, WIDTH};
+    use advent_of_code_2024::generate;
+
+    #[test]
+    fn test_frame() {
+        let robots = parse("p=2,4 v=2,-3\np=2,4 v=0,0\np=0,0 v=1,1\n", 11, 7).unwrap();
+        let frame = frame(&robots, 0, 11, 7);
+
+        assert_eq!((7, 11), (frame.rows(), frame.cols()));
+        assert_eq!(2, frame[Point::new(4, 2)]);
+        assert_eq!(1, frame[Point::new(0, 0)]);
+
+        let text = canvas(&frame).to_text();
+        assert_eq!(Some("1.........."), text.lines().next());
+        assert_eq!(Some("..2........"), text.lines().nth(4));
+    }
+
+    #[test]
+    fn test_scores() {
+        let lonely = [Point::new(0, 0), Point::new(0, 2)];
+        let pair = [Point::new(0, 0), Point::new(1, 1)];
+
+        assert_eq!(1.0, Score::Clustering.score(&lonely, 4, 4));
+        assert_eq!(0.0, Score::Clustering.score(&pair, 4, 4));
+        assert_eq!(0.0, Score::Entropy.score(&pair, 4, 4));
+        assert_eq!(
+            1.0,
+            Score::Entropy.score(&[Point::new(0, 0), Point::new(0, 9)], 10, 10)
+        );
+    }
+
+    // The generated inputs hide a picture, which either score should rank first.
+    #[test]
+    fn test_rank() {
+        for seed in 0..2 {
+            let input = generate::generate("day14", 500, seed).unwrap();
+            let robots = parse(&input, WIDTH, HEIGHT).unwrap();
+            let picture = part2(&input, WIDTH, HEIGHT).unwrap();
+
+            for score in [Score::Entropy, Score::Clustering] {
+                let ranked = rank(&robots, score, 3, WIDTH, HEIGHT);
+
+                assert_eq!(3, ranked.len());
+                assert_eq!(picture, ranked[0].0, "{score:?}");
+                assert!(ranked[0].1 < ranked[1].1);
+            }
+        }
+    }
+}
@@ -0,0 +1,292 @@
+#![cfg_attr(test, feature(test))]
+
+mod frames;
+
+use advent_of_code_2024::input;
+use advent_of_code_2024::number;
+use advent_of_code_2024::parse::{Input, ParseError};
+use frames::Score;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+const WIDTH: i64 = 101;
+const HEIGHT: i64 = 103;
+const SECONDS: i64 = 100;
+
+const USAGE: &str =
+    "usage: day14 [show <seconds> <path> | search <count> <dir> [entropy|clustering]]";
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Robot {
+    x: i64,
+    y: i64,
+    dx: i64,
+    dy: i64,
+}
+
+impl Robot {
+    // Where the robot is after `seconds`, wrapping around the edges of the space.
+    fn position(&self, seconds: i64, width: i64, height: i64) -> (i64, i64) {
+        (
+            (self.x + self.dx * seconds).rem_euclid(width),
+            (self.y + self.dy * seconds).rem_euclid(height),
+        )
+    }
+}
+
+// Robots must start inside the `width` by `height` space. Velocities are reduced to a step
+// forwards along each axis, which wraps to the same place and keeps the arithmetic small.
+fn parse(input: &str, width: i64, height: i64) -> Result<Vec<Robot>, ParseError> {
+    let input = Input::new(input);
+
+    let robots = input.parse_lines(input.as_str(), |line| {
+        let (p, v) = input.split_once(line, " ")?;
+        let [x, y] = input.integers_n(input.strip_prefix(p, "p=")?)?;
+        let [dx, dy] = input.integers_n::<i64, 2>(input.strip_prefix(v, "v=")?)?;
+
+        if !(0..width).contains(&x) || !(0..height).contains(&y) {
+            return Err(input.error(p, format!("a position inside {width} by {height}")));
+        }
+
+        Ok(Robot {
+            x,
+            y,
+            dx: dx.rem_euclid(width),
+            dy: dy.rem_euclid(height),
+        })
+    })?;
+
+    if robots.is_empty() {
+        return Err(input.error(input.as_str(), "at least one robot"));
+    }
+
+    Ok(robots)
+}
+
+// Robots on the middle row or column are in no quadrant.
+fn safety_factor(positions: impl Iterator<Item = (i64, i64)>, width: i64, height: i64) -> usize {
+    let mut quadrants = [0; 4];
+
+    for (x, y) in positions {
+        if x == width / 2 || y == height / 2 {
+            continue;
+        }
+
+        quadrants[usize::from(x > width / 2) * 2 + usize::from(y > height / 2)] += 1;
+    }
+
+    quadrants.iter().product()
+}
+
+fn part1(input: &str, width: i64, height: i64) -> Result<usize, ParseError> {
+    let robots = parse(input, width, height)?;
+
+    Ok(safety_factor(
+        robots
+            .iter()
+            .map(|robot| robot.position(SECONDS, width, height)),
+        width,
+        height,
+    ))
+}
+
+// The first second in `0..period` at which the coordinates picked out by `axis` are least spread
+// out. `n` times the variance, `n·Σx² - (Σx)²`, keeps the comparison in integers.
+fn least_spread(robots: &[Robot], period: i64, axis: impl Fn(&Robot, i64) -> i64) -> i64 {
+    let n = robots.len() as i64;
+
+    (0..period)
+        .min_by_key(|&t| {
+            let (sum, squares) = robots.iter().fold((0, 0), |(sum, squares), robot| {
+                let c = axis(robot, t);
+                (sum + c, squares + c * c)
+            });
+
+            n * squares - sum * sum
+        })
+        .unwrap()
+}
+
+// The robots only form a picture once, when most of them crowd into one place. Each axis repeats
+// on its own, every `width` seconds across and every `height` seconds down, so find when each is
+// least spread out and combine the two with the Chinese remainder theorem.
+fn part2(input: &str, width: i64, height: i64) -> Result<i64, ParseError> {
+    let robots = parse(input, width, height)?;
+
+    let tx = least_spread(&robots, width, |robot, t| {
+        robot.position(t, width, height).0
+    });
+    let ty = least_spread(&robots, height, |robot, t| {
+        robot.position(t, width, height).1
+    });
+
+    number::crt([(tx, width), (ty, height)])
+        .map(|(t, _)| t)
+        .ok_or_else(|| Input::new(input).error(input, "a second when the robots form a picture"))
+}
+
+// Looks at frames instead of solving, e.g. `cargo run --bin day14 -- show 100 -` for a preview in
+// the terminal, or `search 10 frames` to save the ten most orderly frames as `frames/01-<seconds>.png`
+// and so on. Images are saved in whatever format the path's extension names.
+fn inspect(input: &str, mut args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let robots = parse(input, WIDTH, HEIGHT)?;
+    let mut arg = || args.next().ok_or(USAGE);
+
+    match arg()?.as_str() {
+        "show" => {
+            let seconds = arg()?.parse()?;
+
+            frames::canvas(&frames::frame(&robots, seconds, WIDTH, HEIGHT)).save(arg()?, 4)?;
+        }
+        "search" => {
+            let count = arg()?.parse()?;
+            let dir = arg()?;
+            let score = match arg() {
+                Ok(name) => Score::parse(&name).ok_or(USAGE)?,
+                Err(_) => Score::Entropy,
+            };
+
+            fs::create_dir_all(&dir)?;
+
+            let ranked = frames::rank(&robots, score, count, WIDTH, HEIGHT);
+
+            for (rank, &(seconds, score)) in ranked.iter().enumerate() {
+                let path = Path::new(&dir).join(format!("{:02}-{seconds}.png", rank + 1));
+                let frame = frames::frame(&robots, seconds, WIDTH, HEIGHT);
+
+                frames::canvas(&frame).save(&path, 4)?;
+                println!(
+                    "{:>3}. {seconds:>5}s scores {score:.4}: {}",
+                    rank + 1,
+                    path.display()
+                );
+            }
+        }
+        _ => return Err(USAGE.into()),
+    }
+
+    Ok(())
+}
+
+pub fn main() -> Result<(), Box<dyn Error>> {
+    let input = input::read("day14");
+    let mut args = std::env::args().skip(1).peekable();
+
+    if args.peek().is_some() {
+        return inspect(&input, args);
+    }
+
+    let mut now = Instant::now();
+    let part1 = part1(&input, WIDTH, HEIGHT)?;
+    let part1_elapsed = now.elapsed();
+
+    now = Instant::now();
+    let part2 = part2(&input, WIDTH, HEIGHT)?;
+    let part2_elapsed = now.elapsed();
+
+    println!("--- Day 14 ---");
+    println!("Part 1: {}", part1);
+    println!("Part 2: {}", part2);
+    println!("Part 1 took: {:.2?}", part1_elapsed);
+    println!("Part 2 took: {:.2?}", part2_elapsed);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate test;
+    use super::*;
+    use advent_of_code_2024::fuzz;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use test::Bencher;
+
+    const EXAMPLE: &str = "\
+p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3
+";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(12), part1(EXAMPLE, 11, 7));
+    }
+
+    #[test]
+    fn test_wrapping() {
+        let robot = parse("p=2,4 v=2,-3\n", 11, 7).unwrap()[0];
+
+        assert_eq!((4, 1), robot.position(1, 11, 7));
+        assert_eq!((6, 5), robot.position(2, 11, 7));
+        assert_eq!((1, 3), robot.position(5, 11, 7));
+        assert!(parse("p=11,0 v=1,1\n", 11, 7).is_err());
+    }
+
+    // A 20 by 10 block of robots at one second, among as many scattered at random.
+    fn hidden_picture(rng: &mut StdRng, seconds: i64) -> String {
+        (0..400)
+            .map(|i| {
+                let (x, y) = if i < 200 {
+                    (40 + i % 20, 50 + i / 20)
+                } else {
+                    (rng.gen_range(0..WIDTH), rng.gen_range(0..HEIGHT))
+                };
+                let (dx, dy) = (rng.gen_range(-100..=100), rng.gen_range(-100..=100));
+
+                format!(
+                    "p={},{} v={dx},{dy}\n",
+                    (x - dx * seconds).rem_euclid(WIDTH),
+                    (y - dy * seconds).rem_euclid(HEIGHT)
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_picture() {
+        let rng = &mut StdRng::seed_from_u64(0);
+
+        for seconds in [0, 1, 100, 6789, WIDTH * HEIGHT - 1] {
+            assert_eq!(
+                Ok(seconds),
+                part2(&hidden_picture(rng, seconds), WIDTH, HEIGHT)
+            );
+        }
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, |input| part1(input, 11, 7));
+        fuzz::assert_no_panic(EXAMPLE, |input| part2(input, 11, 7));
+    }
+
+    #[bench]
+    fn bench_part1(b: &mut Bencher) {
+        let Some(input) = input::bench("day14") else {
+            return;
+        };
+
+        b.iter(|| part1(&input, WIDTH, HEIGHT));
+    }
+
+    #[bench]
+    fn bench_part2(b: &mut Bencher) {
+        let Some(input) = input::bench("day14") else {
+            return;
+        };
+
+        b.iter(|| part2(&input, WIDTH, HEIGHT));
+    }
+}
@@ -1,15 +1,16 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 
 mod tests;
 
+use advent_of_code_2024::grid::Grid;
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::{Input, ParseError};
+use advent_of_code_2024::point::{Direction, Point};
 use itertools::Itertools;
-use num::Integer;
 use std::cmp::PartialEq;
 use std::collections::HashSet;
 use std::time::Instant;
 
-const N: usize = 100;
-
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 enum CellType {
     Wall,
@@ -18,95 +19,109 @@ enum CellType {
     Space,
 }
 
-#[derive(Copy, Clone, PartialEq)]
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
-}
+fn parse(input: &str) -> Result<(Grid<CellType>, String, Point), ParseError> {
+    let input = Input::new(input);
+    let [grid_str, moves] = input.sections_n()?;
+
+    check_walls(input, grid_str)?;
 
-impl Direction {
-    const fn new(c: char) -> Self {
-        match c {
-            '^' => Self::Up,
-            '>' => Self::Right,
-            'v' => Self::Down,
-            '<' => Self::Left,
-            _ => panic!(),
+    let (grid, robot) = parse_grid_and_robot(grid_str)?;
+
+    for (i, m) in moves.char_indices() {
+        if m != '\n' && Direction::from_char(m).is_none() {
+            return Err(input.error(&moves[i..i + m.len_utf8()], "'^', '>', 'v' or '<'"));
         }
     }
-}
 
-fn updated_coordinate(pos: usize, dir: Direction) -> usize {
-    match dir {
-        Direction::Up => pos - N,
-        Direction::Right => pos + 1,
-        Direction::Down => pos + N,
-        Direction::Left => pos - 1,
-    }
+    Ok((grid, moves.to_owned(), robot))
 }
 
-fn parse(file: &str) -> ([CellType; N * N], String, usize) {
-    let (grid_str, moves) = file.split_once("\n\n").unwrap();
+// The robot is never checked against the edges of the map, so it has to be walled in, and there
+// has to be exactly one of it.
+fn check_walls(input: Input, grid_str: &str) -> Result<(), ParseError> {
+    let lines = grid_str.lines().collect_vec();
+
+    for (x, &line) in lines.iter().enumerate() {
+        let walled = if x == 0 || x == lines.len() - 1 {
+            line.chars().all(|c| c == '#')
+        } else {
+            line.starts_with('#') && line.ends_with('#')
+        };
+
+        if !walled || line.len() != lines[0].len() {
+            return Err(input.error(line, "a rectangular map walled in with '#'"));
+        }
+    }
 
-    let (grid, robot) = parse_grid_and_robot(grid_str.to_owned());
+    let mut robots = grid_str
+        .match_indices('@')
+        .map(|(i, _)| &grid_str[i..i + 1]);
 
-    (grid, moves.to_owned(), robot)
+    match (robots.next(), robots.next()) {
+        (None, _) => Err(input.error(grid_str, "a robot '@'")),
+        (_, Some(extra)) => Err(input.error(extra, "a single robot")),
+        _ => Ok(()),
+    }
 }
 
-fn parse_grid_and_robot(grid_str: String) -> ([CellType; N * N], usize) {
-    let mut robot = 0;
-    let mut grid = [CellType::Space; N * N];
+// Pieces of a map, as used by the tests, need not be walled in or have a robot. Short lines are
+// padded with space.
+fn parse_grid_and_robot(grid_str: &str) -> Result<(Grid<CellType>, Point), ParseError> {
+    let input = Input::new(grid_str);
+
+    let rows = grid_str.lines().count();
+    let cols = grid_str.lines().map(str::len).max().unwrap_or(0);
+
+    let mut robot = Point::ZERO;
+    let mut grid = Grid::new(rows, cols, CellType::Space);
 
     for (x, line) in grid_str.lines().enumerate() {
-        for (y, mut c) in line.chars().enumerate() {
-            if c == '@' {
-                robot = x * N + y;
-                c = '.';
-            }
+        for (y, c) in line.char_indices() {
+            let pos = Point::new(x as isize, y as isize);
 
-            grid[x * N + y] = match c {
+            grid[pos] = match c {
                 '#' => CellType::Wall,
-                'O' | '[' => CellType::Box,
-                ']' => CellType::BoxRight,
-                _ => CellType::Space,
+                'O' => CellType::Box,
+                '[' if line[y + 1..].starts_with(']') => CellType::Box,
+                ']' if line[..y].ends_with('[') => CellType::BoxRight,
+                '.' => CellType::Space,
+                '@' => {
+                    robot = pos;
+                    CellType::Space
+                }
+                _ => {
+                    let text = &line[y..y + c.len_utf8()];
+                    return Err(input.error(text, "'#', '.', 'O', '[]' or '@'"));
+                }
             };
         }
     }
 
-    (grid, robot)
+    Ok((grid, robot))
 }
 
-fn calculate_gps_sum(grid: &[CellType; N * N]) -> usize {
+fn calculate_gps_sum(grid: &Grid<CellType>) -> usize {
     grid.iter()
-        .enumerate()
-        .filter_map(|(i, cell)| {
-            let (x, y) = i.div_rem(&N);
-
-            (cell == &CellType::Box).then_some(100 * x + y)
-        })
-        .sum::<usize>()
+        .filter_map(|(pos, cell)| (cell == &CellType::Box).then_some(100 * pos.x + pos.y))
+        .sum::<isize>() as usize
 }
 
-fn part1() -> usize {
-    let file = include_str!("input.txt");
-    let (mut grid, moves, mut robot) = parse(file);
-
+// Runs every move, returning where the robot ends up.
+fn push_boxes(grid: &mut Grid<CellType>, moves: &str, mut robot: Point) -> Point {
     for m in moves.chars() {
         if m == '\n' {
             continue;
         }
 
-        let dir = Direction::new(m);
-        let neighbour = updated_coordinate(robot, dir);
+        let dir = Direction::from_char(m).unwrap();
+        let neighbour = robot + dir;
 
         match grid[neighbour] {
             CellType::Box => {
-                let mut first_space = updated_coordinate(neighbour, dir);
+                let mut first_space = neighbour + dir;
 
                 while grid[first_space] == CellType::Box {
-                    first_space = updated_coordinate(first_space, dir);
+                    first_space += dir;
                 }
 
                 if grid[first_space] == CellType::Space {
@@ -121,19 +136,27 @@ fn part1() -> usize {
         }
     }
 
-    calculate_gps_sum(&grid)
+    robot
+}
+
+fn part1(input: &str) -> Result<usize, ParseError> {
+    let (mut grid, moves, robot) = parse(input)?;
+
+    push_boxes(&mut grid, &moves, robot);
+
+    Ok(calculate_gps_sum(&grid))
 }
 
 fn get_boxes_to_shift_vertically(
-    grid: &mut [CellType; N * N],
-    box_left_pos: usize,
+    grid: &mut Grid<CellType>,
+    box_left_pos: Point,
     dir: Direction,
-) -> HashSet<usize> {
-    let neighbour = updated_coordinate(box_left_pos, dir);
+) -> HashSet<Point> {
+    let neighbour = box_left_pos + dir;
 
     let mut box_left_set = match grid[neighbour] {
         CellType::Box => get_boxes_to_shift_vertically(grid, neighbour, dir),
-        CellType::BoxRight => get_boxes_to_shift_vertically(grid, neighbour - 1, dir),
+        CellType::BoxRight => get_boxes_to_shift_vertically(grid, neighbour + Direction::Left, dir),
         CellType::Wall => HashSet::new(),
         CellType::Space => HashSet::from([box_left_pos]),
     };
@@ -144,8 +167,8 @@ fn get_boxes_to_shift_vertically(
 
     box_left_set.insert(box_left_pos);
 
-    let box_right_set = match grid[neighbour + 1] {
-        CellType::Box => get_boxes_to_shift_vertically(grid, neighbour + 1, dir),
+    let box_right_set = match grid[neighbour + Direction::Right] {
+        CellType::Box => get_boxes_to_shift_vertically(grid, neighbour + Direction::Right, dir),
         CellType::BoxRight => get_boxes_to_shift_vertically(grid, neighbour, dir),
         CellType::Wall => HashSet::new(),
         CellType::Space => HashSet::from([box_left_pos]),
@@ -160,36 +183,40 @@ fn get_boxes_to_shift_vertically(
     box_left_set
 }
 
-fn shift(grid: &mut [CellType; N * N], robot: &mut usize, dir: Direction, box_left_pos: usize) {
+fn shift(grid: &mut Grid<CellType>, robot: &mut Point, dir: Direction, box_left_pos: Point) {
+    let x = box_left_pos.x;
+    let swap_right =
+        |grid: &mut Grid<CellType>, y| grid.swap(Point::new(x, y), Point::new(x, y + 1));
+
     if dir == Direction::Right {
-        let mut next_space = box_left_pos + 1;
+        let mut next_space = box_left_pos + Direction::Right;
         while grid[next_space] == CellType::Box || grid[next_space] == CellType::BoxRight {
-            next_space += 1;
+            next_space += Direction::Right;
         }
 
         if grid[next_space] == CellType::Space {
-            for pos in (box_left_pos..next_space).rev() {
-                grid.swap(pos, pos + 1);
+            for y in (box_left_pos.y..next_space.y).rev() {
+                swap_right(grid, y);
             }
 
-            *robot += 1;
+            *robot += Direction::Right;
         }
 
         return;
     }
 
     if dir == Direction::Left {
-        let mut next_space = box_left_pos - 1;
+        let mut next_space = box_left_pos + Direction::Left;
         while grid[next_space] == CellType::Box || grid[next_space] == CellType::BoxRight {
-            next_space -= 1;
+            next_space += Direction::Left;
         }
 
         if grid[next_space] == CellType::Space {
-            for pos in next_space..(box_left_pos + 1) {
-                grid.swap(pos, pos + 1);
+            for y in next_space.y..(box_left_pos.y + 1) {
+                swap_right(grid, y);
             }
 
-            *robot -= 1;
+            *robot += Direction::Left;
         }
 
         return;
@@ -198,53 +225,81 @@ fn shift(grid: &mut [CellType; N * N], robot: &mut usize, dir: Direction, box_le
     // vertical
     let boxes_to_shift = get_boxes_to_shift_vertically(grid, box_left_pos, dir);
 
-    if dir == Direction::Up {
-        for &pos in boxes_to_shift.iter().sorted_unstable() {
-            grid.swap(pos, pos - N);
-            grid.swap(pos + 1, pos - N + 1);
-        }
-    } else {
-        for &pos in boxes_to_shift.iter().sorted_unstable().rev() {
-            grid.swap(pos, pos + N);
-            grid.swap(pos + 1, pos + N + 1);
-        }
+    let mut boxes = boxes_to_shift.iter().sorted_unstable().collect_vec();
+
+    if dir == Direction::Down {
+        boxes.reverse();
+    }
+
+    for &pos in boxes {
+        let next = pos + dir;
+        grid.swap(pos, next);
+        grid.swap(pos + Direction::Right, next + Direction::Right);
     }
 
     if !boxes_to_shift.is_empty() {
-        *robot = updated_coordinate(*robot, dir);
+        *robot += dir;
     }
 }
 
-fn part2() -> usize {
-    let file = include_str!("input2.txt");
-    let (mut grid, moves, mut robot) = parse(file);
+// Doubles the width of everything on the map, leaving the moves alone. Anything unexpected is
+// doubled as well, for `parse` to reject.
+fn widen(input: &str) -> String {
+    let Some((grid_str, moves)) = input.split_once("\n\n") else {
+        return input.to_owned();
+    };
+
+    let grid_str = grid_str
+        .chars()
+        .map(|c| match c {
+            'O' => "[]".to_owned(),
+            '@' => "@.".to_owned(),
+            '\n' => "\n".to_owned(),
+            _ => format!("{c}{c}"),
+        })
+        .collect::<String>();
 
+    format!("{grid_str}\n\n{moves}")
+}
+
+// As `push_boxes`, on the widened map.
+fn push_wide_boxes(grid: &mut Grid<CellType>, moves: &str, mut robot: Point) -> Point {
     for m in moves.chars() {
         if m == '\n' {
             continue;
         }
 
-        let dir = Direction::new(m);
-        let neighbour = updated_coordinate(robot, dir);
+        let dir = Direction::from_char(m).unwrap();
+        let neighbour = robot + dir;
 
         match grid[neighbour] {
-            CellType::Box => shift(&mut grid, &mut robot, dir, neighbour),
-            CellType::BoxRight => shift(&mut grid, &mut robot, dir, neighbour - 1),
+            CellType::Box => shift(grid, &mut robot, dir, neighbour),
+            CellType::BoxRight => shift(grid, &mut robot, dir, neighbour + Direction::Left),
             CellType::Space => robot = neighbour,
             _ => {}
         }
     }
 
-    calculate_gps_sum(&grid)
+    robot
+}
+
+fn part2(input: &str) -> Result<usize, ParseError> {
+    let (mut grid, moves, robot) = parse(&widen(input))?;
+
+    push_wide_boxes(&mut grid, &moves, robot);
+
+    Ok(calculate_gps_sum(&grid))
 }
 
-pub fn main() {
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day15");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 15 ---");
@@ -255,4 +310,6 @@ pub fn main() {
 
     assert_eq!(part1, 1398947);
     assert_eq!(part2, 1397393);
+
+    Ok(())
 }
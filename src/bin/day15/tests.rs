@@ -3,61 +3,110 @@ mod unit_tests {
     extern crate test;
 
     use crate::{
-        get_boxes_to_shift_vertically, parse_grid_and_robot, part1, part2, shift, CellType,
-        Direction, N,
+        get_boxes_to_shift_vertically, input, parse, parse_grid_and_robot, part1, part2,
+        push_boxes, push_wide_boxes, shift, widen, CellType, Direction,
     };
+    use advent_of_code_2024::fuzz;
+    use advent_of_code_2024::grid::Grid;
+    use advent_of_code_2024::point::Point;
+    use advent_of_code_2024::snapshot::assert_snapshot;
     use std::collections::HashSet;
     use test::Bencher;
 
+    const SMALL_EXAMPLE: &str = "\
+########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv<v>>v<<
+";
+
+    const LARGE_EXAMPLE: &str = "\
+##########
+#..O..O.O#
+#......O.#
+#.OO..O.O#
+#..O@..O.#
+#O#..O...#
+#O..O..O.#
+#.OO.O.OO#
+#....O...#
+##########
+
+<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
+vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
+><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
+<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
+^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
+^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
+>^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
+<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
+^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
+v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^
+";
+
     #[test]
-    fn test_shift_right() {
-        let (mut grid, mut robot) = parse_grid_and_robot("#.@[][].#".to_owned());
-
-        let dir = Direction::Right;
-        let box_left_pos = 3;
-        shift(&mut grid, &mut robot, dir, box_left_pos);
-
-        // #..@[][]#
-        assert_eq!(
-            [
-                CellType::Wall,
-                CellType::Space,
-                CellType::Space,
-                CellType::Space,
-                CellType::Box,
-                CellType::BoxRight,
-                CellType::Box,
-                CellType::BoxRight,
-                CellType::Wall
-            ],
-            grid[0..9],
+    fn test_examples() {
+        assert_eq!(Ok(2028), part1(SMALL_EXAMPLE));
+        assert_eq!(Ok(10092), part1(LARGE_EXAMPLE));
+        assert_eq!(Ok(9021), part2(LARGE_EXAMPLE));
+    }
+
+    #[test]
+    fn test_widen() {
+        assert_eq!("##....[]@.##\n\n<^\n", widen("#..O@#\n\n<^\n"));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(SMALL_EXAMPLE, part1);
+        fuzz::assert_no_panic(SMALL_EXAMPLE, part2);
+        fuzz::assert_no_panic(LARGE_EXAMPLE, part1);
+        fuzz::assert_no_panic(LARGE_EXAMPLE, part2);
+    }
+
+    #[test]
+    fn test_large_example_final_states() {
+        let (mut grid, moves, robot) = parse(LARGE_EXAMPLE).unwrap();
+        let robot = push_boxes(&mut grid, &moves, robot);
+
+        assert_snapshot(
+            "day15",
+            "large_example_part1",
+            &to_text(&grid, Some(robot), 10, 10),
         );
-        assert_eq!(3, robot);
+
+        let (mut grid, moves, robot) = parse(&widen(LARGE_EXAMPLE)).unwrap();
+        let robot = push_wide_boxes(&mut grid, &moves, robot);
+
+        assert_snapshot(
+            "day15",
+            "large_example_part2",
+            &to_text(&grid, Some(robot), 10, 20),
+        );
+    }
+
+    #[test]
+    fn test_shift_right() {
+        let (mut grid, mut robot) = parse_grid_and_robot("#.@[][].#").unwrap();
+
+        shift(&mut grid, &mut robot, Direction::Right, Point::new(0, 3));
+
+        assert_snapshot("day15", "shift_right", &to_text(&grid, Some(robot), 1, 9));
     }
 
     #[test]
     fn test_shift_left() {
-        let (mut grid, mut robot) = parse_grid_and_robot("#.[][]@.#".to_owned());
-        let dir = Direction::Left;
-        let box_left_pos = 4;
-        shift(&mut grid, &mut robot, dir, box_left_pos);
-
-        // #[][]@..#
-        assert_eq!(
-            [
-                CellType::Wall,
-                CellType::Box,
-                CellType::BoxRight,
-                CellType::Box,
-                CellType::BoxRight,
-                CellType::Space,
-                CellType::Space,
-                CellType::Space,
-                CellType::Wall
-            ],
-            grid[0..9],
-        );
-        assert_eq!(5, robot);
+        let (mut grid, mut robot) = parse_grid_and_robot("#.[][]@.#").unwrap();
+
+        shift(&mut grid, &mut robot, Direction::Left, Point::new(0, 4));
+
+        assert_snapshot("day15", "shift_left", &to_text(&grid, Some(robot), 1, 9));
     }
 
     #[test]
@@ -71,15 +120,16 @@ mod unit_tests {
             #.....#\n\
             #.....#\n\
             #######\n\
-            "
-            .to_owned(),
-        );
-        let box_left_pos = 2 * N + 3;
-        let boxes_to_move = get_boxes_to_shift_vertically(&mut grid, box_left_pos, Direction::Down);
+            ",
+        )
+        .unwrap();
+        let boxes_to_move =
+            get_boxes_to_shift_vertically(&mut grid, Point::new(2, 3), Direction::Down);
 
-        assert_eq!(
-            HashSet::from([2 * N + 3, 3 * N + 2, 3 * N + 4]),
-            boxes_to_move
+        assert_snapshot(
+            "day15",
+            "down_shiftable_boxes",
+            &mark_boxes(&grid, &boxes_to_move, 7),
         );
     }
 
@@ -94,15 +144,16 @@ mod unit_tests {
             #..[].#\n\
             #.....#\n\
             #######\n\
-            "
-            .to_owned(),
-        );
-        let box_left_pos = 2 * N + 3;
-        let boxes_to_move = get_boxes_to_shift_vertically(&mut grid, box_left_pos, Direction::Down);
+            ",
+        )
+        .unwrap();
+        let boxes_to_move =
+            get_boxes_to_shift_vertically(&mut grid, Point::new(2, 3), Direction::Down);
 
-        assert_eq!(
-            HashSet::from([2 * N + 3, 3 * N + 2, 3 * N + 4, 4 * N + 3]),
-            boxes_to_move
+        assert_snapshot(
+            "day15",
+            "down_shiftable_boxes2",
+            &mark_boxes(&grid, &boxes_to_move, 7),
         );
     }
 
@@ -117,11 +168,11 @@ mod unit_tests {
             #...#.#\n\
             #.....#\n\
             #######\n\
-            "
-            .to_owned(),
-        );
-        let box_left_pos = 2 * N + 3;
-        let boxes_to_move = get_boxes_to_shift_vertically(&mut grid, box_left_pos, Direction::Down);
+            ",
+        )
+        .unwrap();
+        let boxes_to_move =
+            get_boxes_to_shift_vertically(&mut grid, Point::new(2, 3), Direction::Down);
 
         assert!(boxes_to_move.is_empty());
     }
@@ -137,29 +188,13 @@ mod unit_tests {
             #..[].#\n\
             #.....#\n\
             #######\n\
-            "
-            .to_owned(),
-        );
-        let dir = Direction::Down;
-        let box_left_pos = 2 * N + 3;
+            ",
+        )
+        .unwrap();
 
-        // When
-        shift(&mut grid, &mut robot, dir, box_left_pos);
+        shift(&mut grid, &mut robot, Direction::Down, Point::new(2, 3));
 
-        // Then
-        assert_eq!(
-            "\
-            #######\n\
-            #.....#\n\
-            #..@..#\n\
-            #..[].#\n\
-            #.[][]#\n\
-            #..[].#\n\
-            #######\n\
-            "
-            .to_owned(),
-            grid_to_print(&grid, robot, 7)
-        );
+        assert_snapshot("day15", "shift_down", &to_text(&grid, Some(robot), 7, 7));
     }
 
     #[test]
@@ -173,30 +208,15 @@ mod unit_tests {
             #..[].#\n\
             #.....#\n\
             #######\n\
-            "
-            .to_owned(),
-        );
-        let dir = Direction::Down;
-        let box_left_pos = 2 * N + 3;
+            ",
+        )
+        .unwrap();
 
-        // When
-        shift(&mut grid, &mut robot, dir, box_left_pos);
+        shift(&mut grid, &mut robot, Direction::Down, Point::new(2, 3));
 
-        // Then
-        assert_eq!(
-            "\
-            #######\n\
-            #.....#\n\
-            #...@.#\n\
-            #..[].#\n\
-            #.[][]#\n\
-            #..[].#\n\
-            #######\n\
-            "
-            .to_owned(),
-            grid_to_print(&grid, robot, 7)
-        );
+        assert_snapshot("day15", "shift_down2", &to_text(&grid, Some(robot), 7, 7));
     }
+
     #[test]
     fn test_shift_down_noop() {
         let (mut grid, mut robot) = parse_grid_and_robot(
@@ -208,28 +228,16 @@ mod unit_tests {
             #...#.#\n\
             #.....#\n\
             #######\n\
-            "
-            .to_owned(),
-        );
-        let dir = Direction::Down;
-        let box_left_pos = 2 * N + 3;
+            ",
+        )
+        .unwrap();
 
-        // When
-        shift(&mut grid, &mut robot, dir, box_left_pos);
+        shift(&mut grid, &mut robot, Direction::Down, Point::new(2, 3));
 
-        // Then
-        assert_eq!(
-            "\
-            #######\n\
-            #..@..#\n\
-            #..[].#\n\
-            #.[][]#\n\
-            #...#.#\n\
-            #.....#\n\
-            #######\n\
-            "
-            .to_owned(),
-            grid_to_print(&grid, robot, 7)
+        assert_snapshot(
+            "day15",
+            "shift_down_noop",
+            &to_text(&grid, Some(robot), 7, 7),
         );
     }
 
@@ -246,13 +254,17 @@ mod unit_tests {
             #.....#\n\
             #.....#\n\
             #######\n\
-            "
-            .to_owned(),
-        );
-        let box_left_pos = 3 * N + 2;
-        let boxes_to_move = get_boxes_to_shift_vertically(&mut grid, box_left_pos, Direction::Up);
+            ",
+        )
+        .unwrap();
+        let boxes_to_move =
+            get_boxes_to_shift_vertically(&mut grid, Point::new(3, 2), Direction::Up);
 
-        assert_eq!(HashSet::from([2 * N + 3, 3 * N + 2,]), boxes_to_move);
+        assert_snapshot(
+            "day15",
+            "up_shiftable_boxes",
+            &mark_boxes(&grid, &boxes_to_move, 7),
+        );
     }
 
     #[test]
@@ -266,15 +278,16 @@ mod unit_tests {
             #..[].#\n\
             #.....#\n\
             #######\n\
-            "
-            .to_owned(),
-        );
-        let box_left_pos = 4 * N + 3;
-        let boxes_to_move = get_boxes_to_shift_vertically(&mut grid, box_left_pos, Direction::Up);
+            ",
+        )
+        .unwrap();
+        let boxes_to_move =
+            get_boxes_to_shift_vertically(&mut grid, Point::new(4, 3), Direction::Up);
 
-        assert_eq!(
-            HashSet::from([2 * N + 3, 3 * N + 2, 3 * N + 4, 4 * N + 3]),
-            boxes_to_move
+        assert_snapshot(
+            "day15",
+            "up_shiftable_boxes2",
+            &mark_boxes(&grid, &boxes_to_move, 7),
         );
     }
 
@@ -289,11 +302,11 @@ mod unit_tests {
             #.....#\n\
             #.....#\n\
             #######\n\
-            "
-            .to_owned(),
-        );
-        let box_left_pos = 3 * N + 2;
-        let boxes_to_move = get_boxes_to_shift_vertically(&mut grid, box_left_pos, Direction::Up);
+            ",
+        )
+        .unwrap();
+        let boxes_to_move =
+            get_boxes_to_shift_vertically(&mut grid, Point::new(3, 2), Direction::Up);
 
         assert!(boxes_to_move.is_empty());
     }
@@ -309,29 +322,13 @@ mod unit_tests {
             #..[].#\n\
             #..@..#\n\
             #######\n\
-            "
-            .to_owned(),
-        );
-        let dir = Direction::Up;
-        let box_left_pos = 5 * N + 3;
+            ",
+        )
+        .unwrap();
 
-        // When
-        shift(&mut grid, &mut robot, dir, box_left_pos);
+        shift(&mut grid, &mut robot, Direction::Up, Point::new(5, 3));
 
-        // Then
-        assert_eq!(
-            "\
-            #######\n\
-            #..[].#\n\
-            #.[][]#\n\
-            #..[].#\n\
-            #..@..#\n\
-            #.....#\n\
-            #######\n\
-            "
-            .to_owned(),
-            grid_to_print(&grid, robot, 7)
-        );
+        assert_snapshot("day15", "shift_up", &to_text(&grid, Some(robot), 7, 7));
     }
 
     #[test]
@@ -345,30 +342,15 @@ mod unit_tests {
             #..[].#\n\
             #...@.#\n\
             #######\n\
-            "
-            .to_owned(),
-        );
-        let dir = Direction::Up;
-        let box_left_pos = 5 * N + 4;
+            ",
+        )
+        .unwrap();
 
-        // When
-        shift(&mut grid, &mut robot, dir, box_left_pos);
+        shift(&mut grid, &mut robot, Direction::Up, Point::new(5, 4));
 
-        // Then
-        assert_eq!(
-            "\
-            #######\n\
-            #..[].#\n\
-            #.[][]#\n\
-            #..[].#\n\
-            #...@.#\n\
-            #.....#\n\
-            #######\n\
-            "
-            .to_owned(),
-            grid_to_print(&grid, robot, 7)
-        );
+        assert_snapshot("day15", "shift_up2", &to_text(&grid, Some(robot), 7, 7));
     }
+
     #[test]
     fn test_shift_up_noop() {
         let (mut grid, mut robot) = parse_grid_and_robot(
@@ -380,64 +362,73 @@ mod unit_tests {
             #..@..#\n\
             #.....#\n\
             #######\n\
-            "
-            .to_owned(),
-        );
-        let dir = Direction::Up;
-        let box_left_pos = 4 * N + 3;
+            ",
+        )
+        .unwrap();
 
-        // When
-        shift(&mut grid, &mut robot, dir, box_left_pos);
+        shift(&mut grid, &mut robot, Direction::Up, Point::new(4, 3));
 
-        // Then
-        assert_eq!(
-            "\
-            #######\n\
-            #...#.#\n\
-            #..[].#\n\
-            #.[][]#\n\
-            #..@..#\n\
-            #.....#\n\
-            #######\n\
-            "
-            .to_owned(),
-            grid_to_print(&grid, robot, 7)
-        );
+        assert_snapshot("day15", "shift_up_noop", &to_text(&grid, Some(robot), 7, 7));
     }
 
-    fn grid_to_print(grid: &[CellType; N * N], robot: usize, n: usize) -> String {
+    // The top left `rows` by `cols` of the warehouse as the puzzle draws it: boxes are `O` on the
+    // original map and `[]` once widened.
+    fn to_text(grid: &Grid<CellType>, robot: Option<Point>, rows: usize, cols: usize) -> String {
         let mut output = String::new();
 
-        for x in 0..n {
-            for y in 0..n {
-                let pos = x * N + y;
-
-                if pos == robot {
-                    output += "@";
-                    continue;
-                }
-
-                output += match grid[pos] {
-                    CellType::Box => "[",
-                    CellType::Wall => "#",
-                    CellType::BoxRight => "]",
-                    CellType::Space => ".",
-                }
+        for x in 0..rows as isize {
+            for y in 0..cols as isize {
+                let pos = Point::new(x, y);
+
+                output.push(match grid[pos] {
+                    _ if Some(pos) == robot => '@',
+                    CellType::Wall => '#',
+                    CellType::Box
+                        if grid.get(pos + Direction::Right) == Some(&CellType::BoxRight) =>
+                    {
+                        '['
+                    }
+                    CellType::Box => 'O',
+                    CellType::BoxRight => ']',
+                    CellType::Space => '.',
+                });
             }
 
-            output += "\n";
+            output.push('\n');
         }
 
         output
     }
 
+    // A square warehouse with `boxes`, given by their left halves, drawn as `{}`.
+    fn mark_boxes(grid: &Grid<CellType>, boxes: &HashSet<Point>, n: usize) -> String {
+        let mut output = to_text(grid, None, n, n).chars().collect::<Vec<_>>();
+
+        for &pos in boxes {
+            let index = pos.x as usize * (n + 1) + pos.y as usize;
+
+            output[index] = '{';
+            output[index + 1] = '}';
+        }
+
+        output.into_iter().collect()
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day15") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day15") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
@@ -1,126 +1,134 @@
-#![feature(test)]
-
+#![cfg_attr(test, feature(test))]
+
+use advent_of_code_2024::grid::Grid;
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::{Input, ParseError};
+use advent_of_code_2024::point::{Direction, Point};
+use advent_of_code_2024::render::{Canvas, Colour, Style};
+use advent_of_code_2024::search::{self, ShortestPaths};
 use itertools::Itertools;
-use std::cmp::Reverse;
-use std::collections::{BinaryHeap, HashSet};
+use std::error::Error;
 use std::time::Instant;
 
-const N: usize = 141;
-
-fn parse() -> ([bool; N * N], usize, usize) {
-    let mut is_space = [true; N * N];
-    let mut src = 0;
-    let mut dest = 0;
+fn parse(input: &str) -> Result<(Grid<bool>, Point, Point), ParseError> {
+    let grid = Grid::parse_with(input, "'#', '.', 'S' or 'E'", |c| {
+        matches!(c, '#' | '.' | 'S' | 'E').then_some(c)
+    })?;
 
-    for (x, line) in include_str!("input.txt").lines().enumerate() {
-        for (y, c) in line.chars().enumerate() {
-            let pos = x * N + y;
+    let find = |tile, expected| {
+        grid.position(|&c| c == tile)
+            .ok_or_else(|| Input::new(input).error(input, expected))
+    };
 
-            if c == 'S' {
-                src = pos;
-                continue;
-            }
+    let src = find('S', "a start tile 'S'")?;
+    let dest = find('E', "an end tile 'E'")?;
 
-            if c == 'E' {
-                dest = pos;
-                continue;
-            }
-
-            if c == '#' {
-                is_space[pos] = false;
-            }
-        }
-    }
+    Ok((grid.map(|_, &c| c != '#'), src, dest))
+}
 
-    (is_space, src, dest)
+fn no_path(input: &str) -> ParseError {
+    Input::new(input).error(input, "a path from 'S' to 'E'")
 }
 
-fn get_turn_weight(dir1: usize, dir2: usize) -> usize {
-    let diff = dir1.abs_diff(dir2);
-    match diff {
-        0..3 => diff * 1000 + 1,
-        _ => 1001,
+fn get_turn_weight(dir1: Direction, dir2: Direction) -> usize {
+    if dir1 == dir2 {
+        1
+    } else if dir1.opposite() == dir2 {
+        2001
+    } else {
+        1001
     }
 }
 
-fn get_neighbours(u: usize) -> [usize; 4] {
-    [u - N, u + 1, u + N, u - 1]
+fn dijkstra(is_space: &Grid<bool>, src: Point) -> ShortestPaths<(Point, Direction)> {
+    search::dijkstra([(src, Direction::Right)], |&(u, dir)| {
+        Direction::ALL
+            .into_iter()
+            .map(move |turn_dir| (turn_dir, u + turn_dir))
+            .filter(|&(_, v)| is_space.get(v) == Some(&true))
+            .map(move |(turn_dir, v)| ((v, turn_dir), get_turn_weight(dir, turn_dir)))
+    })
 }
 
-fn dijkstra(is_space: [bool; N * N], src: usize) -> [[usize; 4]; N * N] {
-    let mut heap = BinaryHeap::from([(Reverse(0), src, 1)]);
-    let mut dist = [[usize::MAX; 4]; N * N];
-    dist[src].fill(0);
-
-    while let Some((_, u, dir)) = heap.pop() {
-        let curr_weight = dist[u][dir];
-
-        for (turn_dir, &v) in get_neighbours(u).iter().enumerate() {
-            if is_space[v] {
-                let weight = get_turn_weight(dir, turn_dir);
-
-                if dist[v][turn_dir] > curr_weight + weight {
-                    dist[v][turn_dir] = curr_weight + weight;
-                    heap.push((Reverse(dist[v][turn_dir]), v, turn_dir));
-                }
-            }
-        }
-    }
-
-    dist
+fn dest_costs(paths: &ShortestPaths<(Point, Direction)>, dest: Point) -> Vec<(Direction, usize)> {
+    Direction::ALL
+        .into_iter()
+        .filter_map(|dir| Some((dir, paths.cost(&(dest, dir))?)))
+        .collect_vec()
 }
 
-fn part1() -> usize {
-    let (is_space, src, dest) = parse();
+fn part1(input: &str) -> Result<usize, ParseError> {
+    let (is_space, src, dest) = parse(input)?;
 
-    let dist = dijkstra(is_space, src);
+    let paths = dijkstra(&is_space, src);
 
-    *dist[dest].iter().min().unwrap()
+    dest_costs(&paths, dest)
+        .into_iter()
+        .map(|(_, cost)| cost)
+        .min()
+        .ok_or_else(|| no_path(input))
 }
 
-fn part2() -> usize {
-    let (is_space, src, dest) = parse();
-
-    let dist = dijkstra(is_space, src);
-
-    let dest_min_cost_dir_idx = dist[dest].iter().position_min().unwrap();
+// Every tile on at least one best path, if there is one.
+fn best_seats(is_space: &Grid<bool>, src: Point, dest: Point) -> Option<Vec<Point>> {
+    let paths = dijkstra(is_space, src);
+
+    let costs = dest_costs(&paths, dest);
+    let min_cost = costs.iter().map(|&(_, cost)| cost).min()?;
+
+    let seats = paths
+        .optimal_states(
+            costs
+                .into_iter()
+                .filter(|&(_, cost)| cost == min_cost)
+                .map(|(dir, _)| (dest, dir)),
+        )
+        .into_iter()
+        .map(|(u, _)| u)
+        .unique()
+        .collect();
+
+    Some(seats)
+}
 
-    let mut path = HashSet::new();
+fn part2(input: &str) -> Result<usize, ParseError> {
+    let (is_space, src, dest) = parse(input)?;
 
-    let mut queue = BinaryHeap::from([(
-        dist[dest][dest_min_cost_dir_idx],
-        dest,
-        dest_min_cost_dir_idx,
-    )]);
+    best_seats(&is_space, src, dest)
+        .map(|seats| seats.len())
+        .ok_or_else(|| no_path(input))
+}
 
-    while let Some((cost, u, dir)) = queue.pop() {
-        path.insert(u);
+// The maze with its best seats marked.
+fn render(input: &str) -> Result<Canvas, ParseError> {
+    let (is_space, src, dest) = parse(input)?;
+    let seats = best_seats(&is_space, src, dest).ok_or_else(|| no_path(input))?;
 
-        if u == src {
-            break;
+    let mut canvas = Canvas::new(&is_space, |_, &space| {
+        if space {
+            Style::new('.', Colour::DARK_GREY)
+        } else {
+            Style::new('#', Colour::GREY)
         }
+    });
 
-        for v in get_neighbours(u) {
-            for (turn_dir, &v_cost) in dist[v].iter().enumerate() {
-                let weight = get_turn_weight(dir, turn_dir);
-
-                if Some(v_cost) == cost.checked_sub(weight) {
-                    queue.push((v_cost, v, turn_dir));
-                }
-            }
-        }
-    }
+    canvas
+        .overlay(seats, Style::new('O', Colour::GREEN))
+        .overlay([src], Style::new('S', Colour::RED))
+        .overlay([dest], Style::new('E', Colour::RED));
 
-    path.len()
+    Ok(canvas)
 }
 
-pub fn main() {
+pub fn main() -> Result<(), Box<dyn Error>> {
+    let input = input::read("day16");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 16 ---");
@@ -129,23 +137,90 @@ pub fn main() {
     println!("Part 1 took: {:.2?}", part1_elapsed);
     println!("Part 2 took: {:.2?}", part2_elapsed);
 
+    if let Some(path) = std::env::args().nth(1) {
+        render(&input)?.save(path, 4)?;
+    }
+
     assert_eq!(part1, 143564);
     assert_eq!(part2, 593);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
     use test::Bencher;
 
+    const EXAMPLE: &str = "\
+###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############
+";
+
+    const SECOND_EXAMPLE: &str = "\
+#################
+#...#...#...#..E#
+#.#.#.#.#.#.#.#.#
+#.#.#.#...#...#.#
+#.#.#.#.###.#.#.#
+#...#.#.#.....#.#
+#.#.#.#.#.#####.#
+#.#...#.#.#.....#
+#.#.#####.#.###.#
+#.#.#.......#...#
+#.#.###.#####.###
+#.#.#...#.....#.#
+#.#.#.#####.###.#
+#.#.#.........#.#
+#.#.#.#########.#
+#S#.............#
+#################
+";
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(Ok(7036), part1(EXAMPLE));
+        assert_eq!(Ok(45), part2(EXAMPLE));
+        assert_eq!(Ok(11048), part1(SECOND_EXAMPLE));
+        assert_eq!(Ok(64), part2(SECOND_EXAMPLE));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, part1);
+        fuzz::assert_no_panic(EXAMPLE, part2);
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day16") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day16") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
@@ -1,107 +1,173 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::{Input, ParseError};
 use itertools::Itertools;
 use std::collections::VecDeque;
 use std::time::Instant;
 
-fn resolve_combo_operand(registers: &[usize; 3], operand: usize) -> usize {
+// Far more than any puzzle program needs, to stop the ones that never halt.
+const MAX_STEPS: usize = 1 << 20;
+
+// Why a program stopped without finishing.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Fault {
+    // It ran for `MAX_STEPS` without halting.
+    NoHalt,
+    // It took 7 as a combo operand. `parse` rejects that where instructions are expected, but a
+    // jump to an odd address runs the operands as instructions instead.
+    ComboSeven,
+}
+
+impl Fault {
+    fn error(self, input: &str) -> ParseError {
+        let expected = match self {
+            Fault::NoHalt => "a program that halts",
+            Fault::ComboSeven => "a program that never takes 7 as a combo operand",
+        };
+
+        Input::new(input).error(input, expected)
+    }
+}
+
+fn resolve_combo_operand(registers: &[usize; 3], operand: usize) -> Result<usize, Fault> {
     match operand {
-        0..=3 => operand,
-        4..=6 => registers[operand - 4],
-        _ => panic!(),
+        0..=3 => Ok(operand),
+        4..=6 => Ok(registers[operand - 4]),
+        _ => Err(Fault::ComboSeven),
     }
 }
 
-fn parse() -> ([usize; 3], Vec<usize>) {
-    let (registers_str, program_str) = include_str!("input.txt").split_once("\n\n").unwrap();
+fn parse(input: &str) -> Result<([usize; 3], Vec<usize>), ParseError> {
+    let input = Input::new(input);
+    let [registers_str, program_str] = input.sections_n()?;
 
-    let mut registers_iter = registers_str
-        .lines()
-        .filter_map(|line| line[12..].parse::<usize>().ok());
+    let registers = input
+        .parse_lines(registers_str, |line| {
+            let (_, value) = input.split_once(line, ": ")?;
+            input.parse(value)
+        })?
+        .try_into()
+        .map_err(|_| input.error(registers_str, "3 registers"))?;
 
-    let mut registers = [0; 3];
-    registers.fill_with(|| registers_iter.next().unwrap());
+    let numbers = input
+        .strip_prefix(program_str, "Program: ")?
+        .split(',')
+        .collect::<Vec<_>>();
 
-    let program = program_str[9..]
-        .chars()
-        .filter_map(|c| {
-            if c == ',' {
-                None
-            } else {
-                c.to_digit(10).map(|i| i as usize)
-            }
-        })
-        .collect_vec();
+    let mut program = vec![];
+
+    for (i, s) in numbers.iter().enumerate() {
+        let number = input.parse(s)?;
+
+        if number > 7 {
+            return Err(input.error(s, "a 3-bit number"));
+        }
+
+        // Every instruction but `bxl`, `jnz` and `bxc` takes a combo operand.
+        if i % 2 == 1 && number == 7 && ![1, 3, 4].contains(&program[i - 1]) {
+            return Err(input.error(s, "a combo operand other than 7"));
+        }
+
+        program.push(number);
+    }
+
+    if program.len() % 2 == 1 {
+        return Err(input.error(program_str, "an operand for every instruction"));
+    }
+
+    Ok((registers, program))
+}
 
-    (registers, program)
+// `A` divided by 2 to the power of `n`.
+fn divide(a: usize, n: usize) -> usize {
+    u32::try_from(n)
+        .ok()
+        .and_then(|n| a.checked_shr(n))
+        .unwrap_or(0)
 }
-fn execute(program: &[usize], mut registers: [usize; 3]) -> Vec<usize> {
+
+// The output of the program, if it halts within `MAX_STEPS` without a fault.
+fn execute(program: &[usize], mut registers: [usize; 3]) -> Result<Vec<usize>, Fault> {
     let mut ptr = 0;
     let mut output = vec![];
 
-    while ptr < program.len() {
+    for _ in 0..MAX_STEPS {
+        if ptr + 1 >= program.len() {
+            return Ok(output);
+        }
+
         let opcode = program[ptr];
         let operand = program[ptr + 1];
-        let combo_operand = resolve_combo_operand(&registers, operand);
         ptr += 2;
 
+        // Only some instructions take a combo operand, and 7 is a valid literal operand.
+        let combo_operand = || resolve_combo_operand(&registers, operand);
+
         match opcode {
-            0 | 6 | 7 => registers[opcode % 5] = registers[0] / 2usize.pow(combo_operand as u32),
+            0 | 6 | 7 => registers[opcode % 5] = divide(registers[0], combo_operand()?),
             1 => registers[1] ^= operand,
-            2 => registers[1] = combo_operand % 8,
+            2 => registers[1] = combo_operand()? % 8,
             3 => {
                 if registers[0] != 0 {
                     ptr = operand
                 }
             }
             4 => registers[1] ^= registers[2],
-            5 => output.push(combo_operand % 8),
-            _ => panic!(),
+            5 => output.push(combo_operand()? % 8),
+            _ => unreachable!("Opcode {opcode}"),
         }
     }
 
-    output
+    Err(Fault::NoHalt)
 }
 
-fn part1() -> String {
-    let (registers, program) = parse();
+fn part1(input: &str) -> Result<String, ParseError> {
+    let (registers, program) = parse(input)?;
 
-    let output = execute(&program, registers);
+    let output = execute(&program, registers).map_err(|fault| fault.error(input))?;
 
-    output.into_iter().join(",")
+    Ok(output.into_iter().join(","))
 }
 
-fn part2() -> usize {
-    let (_, program) = parse();
+// Builds `A` three bits at a time from the top, as each output depends only on the bits of `A`
+// from its own three upwards. Every extra three bits must produce one more matching output, which
+// keeps the search finite whatever the program does.
+fn part2(input: &str) -> Result<usize, ParseError> {
+    let (_, program) = parse(input)?;
 
-    let mut queue = VecDeque::from([0]);
+    let mut queue = VecDeque::from([(0usize, 0)]);
 
-    while let Some(a_head) = queue.pop_front() {
+    while let Some((a_head, matched)) = queue.pop_front() {
         for potential_tail in 0..8 {
-            let a = a_head * 8 + potential_tail;
+            let Some(a) = a_head.checked_mul(8).map(|a| a + potential_tail) else {
+                continue;
+            };
 
-            let attempt = execute(&program, [a, 0, 0]);
+            let attempt = execute(&program, [a, 0, 0]).map_err(|fault| fault.error(input))?;
 
-            if program.ends_with(&attempt) {
+            if attempt.len() > matched && program.ends_with(&attempt) {
                 if program == attempt {
-                    return a;
+                    return Ok(a);
                 }
 
-                queue.push_back(a);
+                queue.push_back((a, attempt.len()));
             }
         }
     }
 
-    panic!("No solution")
+    Err(Input::new(input).error(input, "a program that can output itself"))
 }
 
-pub fn main() {
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day17");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 17 ---");
@@ -112,21 +178,74 @@ pub fn main() {
 
     assert_eq!(part1, "6,7,5,2,1,3,5,1,7");
     assert_eq!(part2, 216549846240877);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
     use test::Bencher;
 
+    const EXAMPLE: &str = "\
+Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0
+";
+
+    const QUINE_EXAMPLE: &str = "\
+Register A: 2024
+Register B: 0
+Register C: 0
+
+Program: 0,3,5,4,3,0
+";
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(Ok("4,6,3,5,6,3,5,2,1,0".to_owned()), part1(EXAMPLE));
+        assert_eq!(Ok(117440), part2(QUINE_EXAMPLE));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, part1);
+        fuzz::assert_no_panic(QUINE_EXAMPLE, part2);
+    }
+
+    #[test]
+    fn test_invalid_programs() {
+        let program = |program| {
+            format!("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: {program}\n")
+        };
+
+        assert!(part1(&program("5,7")).is_err());
+        assert!(part1(&program("1,7,8,0")).is_err());
+        assert!(part1(&program("1,7,5")).is_err());
+        assert!(part1(&program("3,0")).is_err());
+        assert!(part1(&program("3,5,0,0,0,5,7,0")).is_err());
+        assert_eq!(Ok("7".to_owned()), part1(&program("1,7,5,5")));
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day17") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day17") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
@@ -1,112 +1,107 @@
-#![feature(test)]
-
-use num::Integer;
-use std::cmp::Reverse;
-use std::collections::{BinaryHeap, HashSet};
+#![cfg_attr(test, feature(test))]
+
+use advent_of_code_2024::bitset::BitGrid;
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::{Input, ParseError};
+use advent_of_code_2024::point::Point;
+use advent_of_code_2024::search;
+use advent_of_code_2024::union_find::GridUnionFind;
 use std::time::Instant;
 
 const N: usize = 71;
+const BYTES: usize = 1024;
 
-fn get_neighbours(u: usize) -> Vec<usize> {
-    let mut neighbours = vec![];
-
-    if u >= N {
-        neighbours.push(u - N);
-    }
+const START: Point = Point::new(0, 0);
 
-    if u.div_rem(&N).1 < N - 1 {
-        neighbours.push(u + 1);
-    }
-
-    if u < N * (N - 1) {
-        neighbours.push(u + N);
-    }
+// The bottom right corner of an `n` by `n` memory space.
+const fn end(n: usize) -> Point {
+    Point::new(n as isize - 1, n as isize - 1)
+}
 
-    if u.div_rem(&N).1 >= 1 {
-        neighbours.push(u - 1);
-    }
+// Each byte with the line it fell on, which must be within an `n` by `n` memory space.
+fn parse(input: &str, n: usize) -> Result<Vec<(&str, Point)>, ParseError> {
+    let input = Input::new(input);
 
-    neighbours
-}
+    input.parse_lines(input.as_str(), |line| {
+        let (x_str, y_str) = input.split_once(line, ",")?;
 
-fn dijkstra(is_space: [bool; N * N]) -> ([usize; N * N], [Option<usize>; N * N]) {
-    let src = 0;
-    let mut heap = BinaryHeap::from([(Reverse(0), src)]);
-    let mut dist = [usize::MAX; N * N];
-    dist[src] = 0;
-    let mut prev = [None::<usize>; N * N];
-
-    while let Some((_, u)) = heap.pop() {
-        let curr_weight = dist[u];
-
-        for &v in get_neighbours(u).iter() {
-            if is_space[v] && dist[v] > curr_weight + 1 {
-                dist[v] = curr_weight + 1;
-                heap.push((Reverse(dist[v]), v));
-                prev[v] = Some(u);
-            }
-        }
-    }
+        let coordinate = |s| match input.parse::<usize>(s)? {
+            i if i < n => Ok(i as isize),
+            _ => Err(input.error(s, format!("a coordinate below {n}"))),
+        };
 
-    (dist, prev)
+        Ok((line, Point::new(coordinate(y_str)?, coordinate(x_str)?)))
+    })
 }
 
-fn parse_line(line: &str) -> usize {
-    let (x_str, y_str) = line.split_once(",").unwrap();
-    let x = x_str.parse::<usize>().unwrap();
-    let y = y_str.parse::<usize>().unwrap();
-
-    y * N + x
+fn no_path(input: &str) -> ParseError {
+    Input::new(input).error(input, "a path to the exit")
 }
 
-fn part1() -> usize {
-    let mut is_space = [true; N * N];
+fn part1(input: &str, n: usize, bytes: usize) -> Result<usize, ParseError> {
+    let mut corrupted = BitGrid::new(n, n);
 
-    for pos in include_str!("input.txt").lines().take(1024).map(parse_line) {
-        is_space[pos] = false;
+    for (_, pos) in parse(input, n)?.into_iter().take(bytes) {
+        corrupted.insert(pos);
     }
 
-    let (dist, _) = dijkstra(is_space);
-
-    dist[N * N - 1]
+    search::bfs([START], |&u| {
+        u.neighbours()
+            .into_iter()
+            .filter(|&v| v.in_bounds(n, n) && !corrupted.contains(v))
+    })
+    .cost(&end(n))
+    .ok_or_else(|| no_path(input))
 }
 
-fn part2() -> String {
-    let mut is_space = [true; N * N];
-    let mut path = HashSet::new();
+// Drop every byte, then lift them again in reverse: the byte whose removal reconnects the corners
+// is the first one that cut them off. A byte landing where another already has changes nothing, so
+// a cell is only freed once the first byte to land there is lifted.
+fn part2(input: &str, n: usize) -> Result<String, ParseError> {
+    let bytes = parse(input, n)?;
+
+    let mut corrupted = BitGrid::new(n, n);
 
-    for (i, line) in include_str!("input.txt").lines().enumerate() {
-        let pos = parse_line(line);
+    let first = bytes
+        .iter()
+        .map(|&(_, pos)| corrupted.insert(pos))
+        .collect::<Vec<_>>();
 
-        is_space[pos] = false;
+    let mut sets = GridUnionFind::new(n, n);
 
-        if i == 1023 || path.contains(&pos) {
-            let (dist, prev) = dijkstra(is_space);
+    for index in 0..n * n {
+        let pos = Point::from_index(index, n);
 
-            if dist[N * N - 1] == usize::MAX {
-                return line.to_owned();
-            }
+        if !corrupted.contains(pos) {
+            sets.union_neighbours(pos, |neighbour| !corrupted.contains(neighbour));
+        }
+    }
+
+    if sets.connected(START, end(n)) {
+        return Err(Input::new(input).error(input, "bytes that cut off the exit"));
+    }
 
-            path.clear();
+    for (&(line, pos), _) in bytes.iter().zip(first).rev().filter(|&(_, first)| first) {
+        corrupted.remove(pos);
+        sets.union_neighbours(pos, |neighbour| !corrupted.contains(neighbour));
 
-            let mut u = N * N - 1;
-            while let Some(u_prev) = prev[u] {
-                path.insert(u);
-                u = u_prev;
-            }
+        if sets.connected(START, end(n)) {
+            return Ok(line.to_owned());
         }
     }
 
-    panic!("No solution")
+    Err(no_path(input))
 }
 
-pub fn main() {
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day18");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input, N, BYTES)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input, N)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 18 ---");
@@ -117,21 +112,78 @@ pub fn main() {
 
     assert_eq!(part1, 416);
     assert_eq!(part2, "50,23");
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
     use test::Bencher;
 
+    const EXAMPLE: &str = "\
+5,4
+4,2
+4,5
+3,0
+2,1
+6,3
+2,4
+1,5
+0,6
+3,3
+2,6
+5,1
+1,2
+5,5
+2,5
+6,5
+1,4
+0,4
+6,4
+1,1
+6,1
+1,0
+0,5
+1,6
+2,0
+";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(22), part1(EXAMPLE, 7, 12));
+        assert_eq!(Ok("6,1".to_owned()), part2(EXAMPLE, 7));
+    }
+
+    // The second byte at 1,1 must not free it while the first still blocks the way.
+    #[test]
+    fn test_repeated_byte() {
+        assert_eq!(Ok("1,2".to_owned()), part2("1,1\n1,0\n1,2\n1,1\n", 3));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, |input| part1(input, 7, 12));
+        fuzz::assert_no_panic(EXAMPLE, |input| part2(input, 7));
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day18") else {
+            return;
+        };
+
+        b.iter(|| part1(&input, N, BYTES));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day18") else {
+            return;
+        };
+
+        b.iter(|| part2(&input, N));
     }
 }
@@ -1,112 +1,84 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 
-use itertools::Itertools;
-use std::collections::{HashMap, HashSet};
+use advent_of_code_2024::bitset::BitSet;
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::{Input, ParseError};
+use advent_of_code_2024::trie::{AhoCorasick, Trie};
 use std::time::Instant;
 
-fn parse() -> (Vec<String>, String) {
-    let (stripes_str, towels_str) = include_str!("input.txt").split_once("\n\n").unwrap();
+fn parse(input: &str) -> Result<(Trie, &str), ParseError> {
+    let input = Input::new(input);
+    let [stripes_str, towels_str] = input.sections_n()?;
+
     let stripes = stripes_str
         .split(", ")
-        .map(|s| s.to_owned())
-        .sorted_unstable()
-        .collect_vec();
+        .map(|stripe| match stripe.trim() {
+            "" => Err(input.error(stripe, "a pattern")),
+            stripe => Ok(stripe),
+        })
+        .collect::<Result<_, _>>()?;
 
-    (stripes, towels_str.to_owned())
+    Ok((stripes, towels_str))
 }
 
-fn part1() -> u64 {
-    let (stripes, towels_str) = parse();
-
-    let mut ans = 0;
-    let mut reachable = HashSet::new();
-
-    for towel in towels_str.lines() {
-        reachable.clear();
-        reachable.insert(0);
-
-        for idx in 0..towel.len() {
-            if !reachable.contains(&idx) {
-                continue;
-            };
-
-            let mut finished = false;
-
-            let low = stripes.partition_point(|stripe| towel[idx..idx + 1] > stripe[0..1]);
-
-            for stripe in &stripes[low..] {
-                if towel[idx..] == *stripe {
-                    finished = true;
-                    break;
-                }
-
-                if towel[idx..].starts_with(stripe) {
-                    reachable.insert(idx + stripe.len());
-                } else {
-                    let cmp_length = towel[idx..].len().min(stripe.len());
-                    if towel[idx..idx + cmp_length] < stripe[0..cmp_length] {
-                        break;
-                    }
-                }
-            }
-
-            if finished {
-                ans += 1;
-                break;
-            }
+fn is_possible(stripes: &Trie, towel: &[u8]) -> bool {
+    let mut reachable = BitSet::new(towel.len() + 1);
+    reachable.insert(0);
+
+    for idx in 0..towel.len() {
+        if !reachable.contains(idx) {
+            continue;
+        }
+
+        for (_, length) in stripes.prefixes(&towel[idx..]) {
+            reachable.insert(idx + length);
         }
     }
 
-    ans
+    reachable.contains(towel.len())
 }
 
-fn part2() -> u64 {
-    let (stripes, towels_str) = parse();
-
-    let mut ans = 0;
-    let mut parents_count_for_char = HashMap::new();
-
-    for towel in towels_str.lines() {
-        parents_count_for_char.clear();
-        parents_count_for_char.insert(0, 1);
-
-        for idx in 0..towel.len() {
-            let Some(&self_count) = parents_count_for_char.get(&idx) else {
-                continue;
-            };
-
-            let low = stripes.partition_point(|stripe| towel[idx..idx + 1] > stripe[0..1]);
-
-            for stripe in &stripes[low..] {
-                if towel[idx..] == *stripe {
-                    ans += self_count;
-                    continue;
-                }
-
-                if towel[idx..].starts_with(stripe) {
-                    *parents_count_for_char
-                        .entry(idx + stripe.len())
-                        .or_default() += self_count;
-                } else {
-                    let cmp_length = towel[idx..].len().min(stripe.len());
-                    if towel[idx..idx + cmp_length] < stripe[0..cmp_length] {
-                        break;
-                    }
-                }
-            }
-        }
+fn part1(input: &str) -> Result<u64, ParseError> {
+    let (stripes, towels_str) = parse(input)?;
+
+    Ok(towels_str
+        .lines()
+        .filter(|towel| is_possible(&stripes, towel.as_bytes()))
+        .count() as u64)
+}
+
+// Matches arrive in order of their end, by which point every arrangement of the towel up to their
+// start has been counted.
+fn arrangements(stripes: &AhoCorasick, towel: &[u8]) -> u64 {
+    let mut ways = vec![0; towel.len() + 1];
+    ways[0] = 1;
+
+    for m in stripes.find_overlapping(towel) {
+        ways[m.end] += ways[m.start];
     }
 
-    ans
+    ways[towel.len()]
+}
+
+fn part2(input: &str) -> Result<u64, ParseError> {
+    let (stripes, towels_str) = parse(input)?;
+    let stripes = AhoCorasick::new(stripes);
+
+    Ok(towels_str
+        .lines()
+        .map(|towel| arrangements(&stripes, towel.as_bytes()))
+        .sum())
 }
 
-pub fn main() {
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day19");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 19 ---");
@@ -117,21 +89,57 @@ pub fn main() {
 
     assert_eq!(part1, 300);
     assert_eq!(part2, 624802218898092);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
     use test::Bencher;
 
+    const EXAMPLE: &str = "\
+r, wr, b, g, bwu, rb, gb, br
+
+brwrr
+bggr
+gbbr
+rrbgbr
+ubwu
+bwurrg
+brgr
+bbrgwb
+";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(6), part1(EXAMPLE));
+        assert_eq!(Ok(16), part2(EXAMPLE));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, part1);
+        fuzz::assert_no_panic(EXAMPLE, part2);
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day19") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day19") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
@@ -1,116 +1,84 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 
+use advent_of_code_2024::grid::Grid;
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::{Input, ParseError};
+use advent_of_code_2024::point::{Direction, Point};
+use advent_of_code_2024::search;
 use itertools::Itertools;
-use num::Integer;
 use std::collections::HashSet;
-use std::mem::{swap, take};
+use std::mem::swap;
 use std::time::Instant;
 
-const N: usize = 141;
+const SAVING: usize = 100;
 
-const CARDINALS: [isize; 4] = [-(N as isize), 1, N as isize, -1];
+// Distances along the shortest path, `usize::MAX` off it, and the path itself.
+type Race = (Grid<usize>, Vec<Point>);
 
-fn dijkstra(is_space: [bool; N * N], src: usize) -> ([usize; N * N], Vec<usize>) {
-    let mut dist = [usize::MAX; N * N];
-    dist[src] = 0;
-    let mut path = vec![];
+fn race(is_space: &Grid<bool>, src: Point, dest: Point) -> Option<Race> {
+    let path = search::bfs([src], |&u| {
+        u.neighbours()
+            .into_iter()
+            .filter(|&v| is_space.get(v) == Some(&true))
+    })
+    .path(&dest)?;
 
-    let mut curr = Some(src);
+    let mut dist = Grid::new(is_space.rows(), is_space.cols(), usize::MAX);
 
-    while let Some(u) = take(&mut curr) {
-        path.push(u);
-
-        let curr_weight = dist[u];
-
-        for du in CARDINALS {
-            let Some(v) = u.checked_add_signed(du) else {
-                continue;
-            };
-
-            if is_space[v] && dist[v] > curr_weight + 1 {
-                dist[v] = curr_weight + 1;
-                curr = Some(v);
-            }
-        }
+    for (i, &u) in path.iter().enumerate() {
+        dist[u] = i;
     }
 
-    (dist, path)
+    Some((dist, path))
 }
 
-fn parse() -> ([bool; N * N], usize) {
-    let mut is_space = [true; N * N];
-    let mut s = 0;
+// The track, with the distances along it from the start.
+fn parse(input: &str) -> Result<(Grid<bool>, Race), ParseError> {
+    let grid = Grid::parse_with(input, "'#', '.', 'S' or 'E'", |c| {
+        matches!(c, '#' | '.' | 'S' | 'E').then_some(c)
+    })?;
 
-    for (x, line) in include_str!("input.txt").lines().enumerate() {
-        for (y, c) in line.chars().enumerate() {
-            let pos = x * N + y;
+    let find = |tile, expected| {
+        grid.position(|&c| c == tile)
+            .ok_or_else(|| Input::new(input).error(input, expected))
+    };
 
-            is_space[pos] = match c {
-                '.' | 'E' => true,
-                'S' => {
-                    s = pos;
-                    true
-                }
-                '#' => false,
-                _ => panic!(),
-            };
-        }
-    }
+    let s = find('S', "a start tile 'S'")?;
+    let e = find('E', "an end tile 'E'")?;
 
-    (is_space, s)
-}
-
-fn get_2_spaces_over(is_space: &[bool; N * N], u: usize) -> Vec<usize> {
-    let mut vec = vec![];
-
-    // Vertical
-    for du in [-(N as isize), N as isize] {
-        let Some(w) = u.checked_add_signed(du * 2) else {
-            continue;
-        };
+    let is_space = grid.map(|_, &c| c != '#');
 
-        if w >= (N * N) || !is_space[w] {
-            continue;
-        }
+    let race = race(&is_space, s, e)
+        .ok_or_else(|| Input::new(input).error(input, "a track from 'S' to 'E'"))?;
 
-        vec.push(w);
-    }
-
-    // Horizontal
-    for du in [-1, 1] {
-        let Some(w) = u.checked_add_signed(du * 2) else {
-            continue;
-        };
-
-        if w >= (N * N) || (u / N) != (w / N) || !is_space[w] {
-            continue;
-        }
-
-        vec.push(w);
-    }
-
-    vec
+    Ok((is_space, race))
 }
 
-fn manhattan_dist(u: &usize, v: &usize) -> usize {
-    let (ux, uy) = u.div_rem(&N);
-    let (vx, vy) = v.div_rem(&N);
-
-    let (dx, dy) = (ux.abs_diff(vx), uy.abs_diff(vy));
-
-    dx + dy
+// Every space a 2 picosecond cheat can reach, either straight through a wall or diagonally
+// around a corner.
+fn get_2_spaces_over(is_space: &Grid<bool>, u: Point) -> Vec<Point> {
+    Direction::ALL
+        .iter()
+        .flat_map(|&dir| [dir.offset() * 2, dir.offset() + dir.turn_right().offset()])
+        .map(|offset| u + offset)
+        .filter(|&w| is_space.get(w) == Some(&true))
+        .collect()
 }
 
-fn part1() -> usize {
-    let (is_space, s) = parse();
-
-    let (dist, path) = dijkstra(is_space, s);
+// Number of cheats lasting at most 2 picoseconds that save at least `saving`.
+fn part1(input: &str, saving: usize) -> Result<usize, ParseError> {
+    let (is_space, (dist, path)) = parse(input)?;
 
     let mut cheats = HashSet::new();
     let mut ans = 0;
 
     for u in path {
+        // Spaces off the path only exist if the track branches, which the puzzle rules out.
         for mut v in get_2_spaces_over(&is_space, u) {
+            if dist[v] == usize::MAX {
+                continue;
+            }
+
             let mut u = u;
 
             if dist[u] > dist[v] {
@@ -120,24 +88,23 @@ fn part1() -> usize {
             if cheats.insert((u, v)) {
                 let new_dist_v = dist[u] + 2;
 
-                if dist[v] - new_dist_v >= 100 {
+                if dist[v].saturating_sub(new_dist_v) >= saving {
                     ans += 1;
                 }
             }
         }
     }
 
-    ans
+    Ok(ans)
 }
 
-fn part2() -> usize {
-    let (is_space, s) = parse();
-
-    let (dist, path) = dijkstra(is_space, s);
+// Number of cheats lasting at most 20 picoseconds that save at least `saving`.
+fn part2(input: &str, saving: usize) -> Result<usize, ParseError> {
+    let (_, (dist, path)) = parse(input)?;
     let mut ans = 0;
 
     for (&u, &v) in path.iter().tuple_combinations() {
-        let distance = manhattan_dist(&u, &v);
+        let distance = u.manhattan(v);
 
         if distance > 20 {
             continue;
@@ -145,21 +112,23 @@ fn part2() -> usize {
 
         let new_dist_v = dist[u] + distance;
 
-        if dist[v].saturating_sub(new_dist_v) >= 100 {
+        if dist[v].saturating_sub(new_dist_v) >= saving {
             ans += 1;
         }
     }
 
-    ans
+    Ok(ans)
 }
 
-pub fn main() {
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day20");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input, SAVING)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input, SAVING)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 20 ---");
@@ -170,21 +139,191 @@ pub fn main() {
 
     assert_eq!(part1, 1355);
     assert_eq!(part2, 1007335);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
+    use proptest::prelude::*;
+    use std::collections::{HashMap, VecDeque};
     use test::Bencher;
 
+    const EXAMPLE: &str = "\
+###############
+#...#...#.....#
+#.#.#.#.#.###.#
+#S#...#.#.#...#
+#######.#.#.###
+#######.#.#...#
+#######.#.###.#
+###..E#...#...#
+###.#######.###
+#...###...#...#
+#.#####.#.###.#
+#.#...#.#.#...#
+#.#.#.#.#.#.###
+#...#...#...###
+###############
+";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(1), part1(EXAMPLE, 64));
+        assert_eq!(Ok(5), part1(EXAMPLE, 20));
+        assert_eq!(Ok(3), part2(EXAMPLE, 76));
+        assert_eq!(Ok(285), part2(EXAMPLE, 50));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, |input| part1(input, 20));
+        fuzz::assert_no_panic(EXAMPLE, |input| part2(input, 50));
+    }
+
+    #[test]
+    fn test_diagonal_cheat() {
+        let input = "\
+#########
+#S##....#
+#.##.##.#
+#.##.##.#
+#.#..##.#
+#...#E..#
+#########
+";
+
+        // Includes cutting the corner from just above the wall left of E.
+        assert_eq!(Ok(3), part1(input, 1));
+    }
+
+    fn distances(track: &HashSet<Point>, from: Point) -> HashMap<Point, usize> {
+        let mut dist = HashMap::from([(from, 0)]);
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(u) = queue.pop_front() {
+            for v in u.neighbours() {
+                if track.contains(&v) && !dist.contains_key(&v) {
+                    dist.insert(v, dist[&u] + 1);
+                    queue.push_back(v);
+                }
+            }
+        }
+
+        dist
+    }
+
+    // Tries every pair of track positions as the start and end of a cheat.
+    fn cheats_oracle(input: &str, max_length: usize, saving: usize) -> usize {
+        let grid = Grid::parse(input).unwrap();
+        let track = grid
+            .iter()
+            .filter(|&(_, &c)| c != '#')
+            .map(|(pos, _)| pos)
+            .collect::<HashSet<_>>();
+
+        let from_start = distances(&track, grid.position(|&c| c == 'S').unwrap());
+        let to_end = distances(&track, grid.position(|&c| c == 'E').unwrap());
+        let fair = to_end[&grid.position(|&c| c == 'S').unwrap()];
+
+        track
+            .iter()
+            .cartesian_product(&track)
+            .filter(|&(&u, &v)| {
+                let length = u.manhattan(v);
+
+                length <= max_length && from_start[&u] + length + to_end[&v] + saving <= fair
+            })
+            .count()
+    }
+
+    // A random walk from the top left corner that never steps next to an earlier part of itself,
+    // so the track has no branches or shortcuts.
+    fn racetracks() -> impl Strategy<Value = String> {
+        (
+            4..12usize,
+            4..12usize,
+            prop::collection::vec(0..4usize, 1..100),
+        )
+            .prop_map(|(rows, cols, choices)| {
+                let inside = |p: Point| {
+                    0 < p.x && p.x < rows as isize - 1 && 0 < p.y && p.y < cols as isize - 1
+                };
+
+                let mut track = vec![Point::new(1, 1)];
+
+                for choice in choices {
+                    let u = *track.last().unwrap();
+                    let candidates = u
+                        .neighbours()
+                        .into_iter()
+                        .filter(|&v| {
+                            inside(v)
+                                && v.neighbours()
+                                    .iter()
+                                    .all(|&w| w == u || !track.contains(&w))
+                                && !track.contains(&v)
+                        })
+                        .collect_vec();
+
+                    if candidates.is_empty() {
+                        break;
+                    }
+
+                    track.push(candidates[choice % candidates.len()]);
+                }
+
+                let mut grid = Grid::new(rows, cols, '#');
+
+                for &pos in &track {
+                    grid[pos] = '.';
+                }
+
+                grid[track[0]] = 'S';
+                grid[*track.last().unwrap()] = 'E';
+
+                grid.points()
+                    .map(|pos| grid[pos])
+                    .chunks(cols)
+                    .into_iter()
+                    .map(|row| row.collect::<String>() + "\n")
+                    .collect()
+            })
+            .prop_filter("track needs distinct ends", |input: &String| {
+                input.contains('S')
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn test_part1_matches_oracle(input in racetracks(), saving in 1..20usize) {
+            prop_assert_eq!(Ok(cheats_oracle(&input, 2, saving)), part1(&input, saving));
+        }
+
+        #[test]
+        fn test_part2_matches_oracle(input in racetracks(), saving in 1..20usize) {
+            prop_assert_eq!(Ok(cheats_oracle(&input, 20, saving)), part2(&input, saving));
+        }
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day20") else {
+            return;
+        };
+
+        b.iter(|| part1(&input, SAVING));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day20") else {
+            return;
+        };
+
+        b.iter(|| part2(&input, SAVING));
     }
 }
@@ -0,0 +1,295 @@
+#![cfg_attr(test, feature(test))]
+
+use advent_of_code_2024::input;
+use advent_of_code_2024::memo::Memo;
+use advent_of_code_2024::parse::{Input, ParseError};
+use advent_of_code_2024::point::Point;
+use itertools::Itertools;
+use std::error::Error;
+use std::iter;
+use std::time::Instant;
+
+// Rows of keys, with a space for the gap that no arm may point at.
+const NUMERIC: [&str; 4] = ["789", "456", "123", " 0A"];
+const DIRECTIONAL: [&str; 2] = [" ^A", "<v>"];
+
+fn position(keypad: &[&str], key: char) -> Option<Point> {
+    keypad
+        .iter()
+        .enumerate()
+        .find_map(|(x, row)| row.find(key).map(|y| Point::new(x as isize, y as isize)))
+}
+
+// The presses on a directional keypad that move an arm from `from` to `to` on `keypad` and press
+// it. Turning as little as possible is always cheapest, so only the two L-shaped routes are
+// worth trying, less any that cross the gap.
+fn routes(keypad: &[&str], from: char, to: char) -> Vec<String> {
+    let (start, end) = (
+        position(keypad, from).unwrap(),
+        position(keypad, to).unwrap(),
+    );
+    let gap = position(keypad, ' ').unwrap();
+    let diff = end - start;
+
+    let vertical = if diff.x < 0 { '^' } else { 'v' };
+    let horizontal = if diff.y < 0 { '<' } else { '>' };
+    let vertical = iter::repeat_n(vertical, diff.x.unsigned_abs());
+    let horizontal = iter::repeat_n(horizontal, diff.y.unsigned_abs());
+
+    let mut routes = vec![];
+
+    if gap != Point::new(start.x, end.y) {
+        routes.push(
+            horizontal
+                .clone()
+                .chain(vertical.clone())
+                .collect::<String>()
+                + "A",
+        );
+    }
+
+    if gap != Point::new(end.x, start.y) {
+        routes.push(vertical.chain(horizontal).collect::<String>() + "A");
+    }
+
+    routes.dedup();
+    routes
+}
+
+type Key = (char, char, usize);
+
+// Memoises what it costs the person at the front of the chain to have the arm `robots` keypads
+// away move between two keys and press the second.
+type Costs = Memo<Key, u64, fn(&mut dyn FnMut(Key) -> u64, Key) -> u64>;
+
+fn costs() -> Costs {
+    Memo::new(pair_cost)
+}
+
+// The person's own presses cost one each. Further down the chain, the arm is moved by typing one
+// of its routes on the keypad before it.
+fn pair_cost(cost: &mut dyn FnMut(Key) -> u64, (from, to, robots): Key) -> u64 {
+    if robots == 0 {
+        return 1;
+    }
+
+    routes(&DIRECTIONAL, from, to)
+        .iter()
+        .map(|route| sequence_cost(cost, route, robots - 1))
+        .min()
+        .unwrap()
+}
+
+// What it costs to type `sequence` on a directional keypad `robots` keypads away, starting from
+// and so ending on 'A'.
+fn sequence_cost(cost: &mut dyn FnMut(Key) -> u64, sequence: &str, robots: usize) -> u64 {
+    iter::once('A')
+        .chain(sequence.chars())
+        .tuple_windows()
+        .map(|(from, to)| cost((from, to, robots)))
+        .sum()
+}
+
+// The cheapest route between two keys on `keypad`, which is `robots` keypads away.
+fn best_route(costs: &mut Costs, keypad: &[&str], from: char, to: char, robots: usize) -> String {
+    routes(keypad, from, to)
+        .into_iter()
+        .min_by_key(|route| sequence_cost(&mut |key| costs.get(key), route, robots))
+        .unwrap()
+}
+
+// Fewest presses to type `code` on the numeric keypad, through `robots` directional keypads.
+fn code_cost(costs: &mut Costs, code: &str, robots: usize) -> u64 {
+    iter::once('A')
+        .chain(code.chars())
+        .tuple_windows()
+        .map(|(from, to)| {
+            let route = best_route(costs, &NUMERIC, from, to, robots);
+            sequence_cost(&mut |key| costs.get(key), &route, robots)
+        })
+        .sum()
+}
+
+// A shortest sequence of presses for the person to type `code`, as for `code_cost`. It grows
+// exponentially with `robots`, so this is only for looking at short chains.
+fn presses(costs: &mut Costs, code: &str, robots: usize) -> String {
+    let mut sequence = iter::once('A')
+        .chain(code.chars())
+        .tuple_windows()
+        .map(|(from, to)| best_route(costs, &NUMERIC, from, to, robots))
+        .collect::<String>();
+
+    for robots in (0..robots).rev() {
+        sequence = iter::once('A')
+            .chain(sequence.chars())
+            .tuple_windows()
+            .map(|(from, to)| best_route(costs, &DIRECTIONAL, from, to, robots))
+            .collect();
+    }
+
+    sequence
+}
+
+// Each code with its numeric part: digits, ending in 'A'.
+fn parse(input: &str) -> Result<Vec<(&str, u64)>, ParseError> {
+    let input = Input::new(input);
+
+    input.parse_lines(input.as_str(), |line| {
+        let digits = line
+            .strip_suffix('A')
+            .filter(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+            .ok_or_else(|| input.error(line, "digits followed by 'A'"))?;
+
+        Ok((line, input.parse(digits)?))
+    })
+}
+
+// The sum of each code's complexity: its numeric part times the fewest presses needed to type it.
+fn complexity(input: &str, robots: usize) -> Result<u64, ParseError> {
+    let mut costs = costs();
+
+    parse(input)?
+        .into_iter()
+        .try_fold(0u64, |sum, (code, number)| {
+            code_cost(&mut costs, code, robots)
+                .checked_mul(number)
+                .and_then(|complexity| sum.checked_add(complexity))
+        })
+        .ok_or_else(|| Input::new(input).error(input, "complexities that fit in a u64"))
+}
+
+fn part1(input: &str) -> Result<u64, ParseError> {
+    complexity(input, 2)
+}
+
+fn part2(input: &str) -> Result<u64, ParseError> {
+    complexity(input, 25)
+}
+
+pub fn main() -> Result<(), Box<dyn Error>> {
+    let input = input::read("day21");
+
+    let mut now = Instant::now();
+    let part1 = part1(&input)?;
+    let part1_elapsed = now.elapsed();
+
+    now = Instant::now();
+    let part2 = part2(&input)?;
+    let part2_elapsed = now.elapsed();
+
+    println!("--- Day 21 ---");
+    println!("Part 1: {}", part1);
+    println!("Part 2: {}", part2);
+    println!("Part 1 took: {:.2?}", part1_elapsed);
+    println!("Part 2 took: {:.2?}", part2_elapsed);
+
+    // e.g. `cargo run --bin day21 -- 2` for the presses behind part 1.
+    if let Some(robots) = std::env::args().nth(1) {
+        let robots = robots.parse()?;
+        let mut costs = costs();
+
+        for (code, _) in parse(&input)? {
+            println!("{code}: {}", presses(&mut costs, code, robots));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate test;
+    use super::*;
+    use advent_of_code_2024::fuzz;
+    use advent_of_code_2024::point::Direction;
+    use test::Bencher;
+
+    const EXAMPLE: &str = "\
+029A
+980A
+179A
+456A
+379A
+";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(126384), part1(EXAMPLE));
+        assert_eq!(Ok(154115708116294), part2(EXAMPLE));
+    }
+
+    #[test]
+    fn test_routes_avoid_gap() {
+        assert_eq!(vec!["^^^<<A"], routes(&NUMERIC, 'A', '7'));
+        assert_eq!(vec![">>vvvA"], routes(&NUMERIC, '7', 'A'));
+        assert_eq!(vec!["v<<A"], routes(&DIRECTIONAL, 'A', '<'));
+        assert_eq!(vec![">^A", "^>A"], routes(&DIRECTIONAL, 'v', 'A'));
+        assert_eq!(vec!["A"], routes(&NUMERIC, '5', '5'));
+    }
+
+    // Types `presses` on a directional keypad, returning what the arm it controls presses.
+    fn type_presses(presses: &str, keypad: &[&str]) -> String {
+        let mut arm = position(keypad, 'A').unwrap();
+        let mut pressed = String::new();
+
+        for c in presses.chars() {
+            match Direction::from_char(c) {
+                Some(dir) => arm += dir,
+                None => pressed.push(keypad[arm.x as usize].as_bytes()[arm.y as usize] as char),
+            }
+
+            assert_ne!(
+                Some(arm),
+                position(keypad, ' '),
+                "{presses} crosses the gap"
+            );
+        }
+
+        pressed
+    }
+
+    #[test]
+    fn test_presses() {
+        let mut costs = costs();
+
+        for (code, _) in parse(EXAMPLE).unwrap() {
+            for robots in 0..4 {
+                let mut presses = presses(&mut costs, code, robots);
+
+                assert_eq!(code_cost(&mut costs, code, robots), presses.len() as u64);
+
+                for _ in 0..robots {
+                    presses = type_presses(&presses, &DIRECTIONAL);
+                }
+
+                assert_eq!(code, type_presses(&presses, &NUMERIC));
+            }
+        }
+
+        assert_eq!(68, presses(&mut costs, "029A", 2).len());
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, part1);
+        fuzz::assert_no_panic(EXAMPLE, part2);
+    }
+
+    #[bench]
+    fn bench_part1(b: &mut Bencher) {
+        let Some(input) = input::bench("day21") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
+    }
+
+    #[bench]
+    fn bench_part2(b: &mut Bencher) {
+        let Some(input) = input::bench("day21") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
+    }
+}
@@ -1,5 +1,7 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::{Input, ParseError};
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
 use std::time::Instant;
@@ -23,26 +25,31 @@ fn get_secret_numbers(mut num: i64) -> [i64; 2001] {
     sequence
 }
 
-fn part1() -> i64 {
-    let mut ans = 0;
+// Initial secret numbers fit in 24 bits, so a `u32` leaves room for the first multiplication.
+fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+    let input = Input::new(input);
+
+    input.parse_lines(input.as_str(), |line| {
+        input.parse::<u32>(line).map(i64::from)
+    })
+}
 
-    for line in include_str!("input.txt").lines() {
-        let num = line.parse::<i64>().unwrap();
+fn part1(input: &str) -> Result<i64, ParseError> {
+    let mut ans = 0;
 
+    for num in parse(input)? {
         ans += get_secret_numbers(num)[2000];
     }
 
-    ans
+    Ok(ans)
 }
 
-fn part2() -> i64 {
+fn part2(input: &str) -> Result<i64, ParseError> {
     let mut sequences = HashMap::new();
     let mut line_seq = HashSet::<(i64, i64, i64, i64)>::new();
     let mut secret_numbers: [i64; 2001];
 
-    for line in include_str!("input.txt").lines() {
-        let num = line.parse::<i64>().unwrap();
-
+    for num in parse(input)? {
         secret_numbers = get_secret_numbers(num).map(|n| n % 10);
         line_seq.clear();
 
@@ -54,16 +61,21 @@ fn part2() -> i64 {
             .for_each(|(i, window)| *sequences.entry(window).or_default() += secret_numbers[i + 4]);
     }
 
-    *sequences.values().max().unwrap()
+    sequences
+        .into_values()
+        .max()
+        .ok_or_else(|| Input::new(input).error(input, "at least one buyer"))
 }
 
-pub fn main() {
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day22");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 22 ---");
@@ -74,21 +86,47 @@ pub fn main() {
 
     assert_eq!(part1, 20506453102);
     assert_eq!(part2, 2423);
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
     use test::Bencher;
 
+    const EXAMPLE1: &str = "1\n10\n100\n2024\n";
+    const EXAMPLE2: &str = "1\n2\n3\n2024\n";
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(Ok(37327623), part1(EXAMPLE1));
+        assert_eq!(Ok(23), part2(EXAMPLE2));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE1, part1);
+        fuzz::assert_no_panic(EXAMPLE2, part2);
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day22") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day22") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
@@ -1,120 +1,123 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 
+use advent_of_code_2024::graph::Graph;
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::ParseError;
 use itertools::Itertools;
-use std::collections::{HashMap, HashSet, VecDeque};
 use std::time::Instant;
 
-fn parse() -> HashMap<String, HashSet<String>> {
-    let mut graph = HashMap::<String, HashSet<String>>::new();
-
-    for line in include_str!("input.txt").lines() {
-        let (lhs, rhs) = line.split_once("-").unwrap();
-        let lhs = lhs.to_string();
-        let rhs = rhs.to_string();
+fn parse(input: &str) -> Result<Graph, ParseError> {
+    Graph::parse_edges(input, '-', false)
+}
 
-        graph.entry(lhs.clone()).or_default().insert(rhs.clone());
-        graph.entry(rhs).or_default().insert(lhs);
-    }
+fn later_neighbours(graph: &Graph, u: usize) -> &[usize] {
+    let neighbours = graph.neighbours(u);
 
-    graph
+    &neighbours[neighbours.partition_point(|&v| v <= u)..]
 }
 
-fn part1() -> usize {
-    let graph = parse();
-
-    let mut paths = HashSet::new();
-
-    for curr in graph.keys().filter(|node| node.starts_with("t")) {
-        let mut queue = VecDeque::from([[Some(curr), None, None]]);
-
-        while let Some(possible_path) = queue.pop_back() {
-            let p = possible_path.partition_point(|node| node.is_some());
-
-            if p == possible_path.len() {
-                if graph
-                    .get(possible_path[possible_path.len() - 1].unwrap())
-                    .is_some_and(|set| set.contains(curr))
-                {
-                    paths.insert(
-                        possible_path
-                            .iter()
-                            .copied()
-                            .flatten()
-                            .sorted_unstable()
-                            .collect_vec(),
-                    );
-                }
-
-                continue;
-            }
+fn part1(input: &str) -> Result<usize, ParseError> {
+    let graph = parse(input)?;
 
-            let neighbours = graph.get(possible_path[p - 1].unwrap()).unwrap();
+    let is_t = |u: usize| graph.label(u).starts_with('t');
 
-            for node in neighbours {
-                if !possible_path.contains(&Some(node)) {
-                    let mut possible_path = possible_path;
-                    possible_path[p] = Some(node);
+    let mut ans = 0;
 
-                    queue.push_back(possible_path);
+    for a in graph.nodes() {
+        for &b in later_neighbours(&graph, a) {
+            for &c in later_neighbours(&graph, b) {
+                if graph.has_edge(a, c) && (is_t(a) || is_t(b) || is_t(c)) {
+                    ans += 1;
                 }
             }
         }
     }
 
-    paths.len()
+    Ok(ans)
 }
 
-fn part2() -> String {
-    let graph = parse();
-
-    let mut longest_path = String::new();
-    let mut visited = HashSet::new();
-
-    for curr in graph.keys() {
-        let mut queue =
-            VecDeque::<(HashSet<String>, String)>::from([(HashSet::new(), curr.clone())]);
-
-        while let Some((mut possible_path, new_node)) = queue.pop_back() {
-            let neighbours = graph.get(&new_node).unwrap();
-
-            if !neighbours.is_superset(&possible_path) {
-                continue;
-            }
+fn intersect(lhs: &[usize], rhs: &[usize]) -> Vec<usize> {
+    lhs.iter()
+        .filter(|u| rhs.binary_search(u).is_ok())
+        .copied()
+        .collect_vec()
+}
 
-            if !possible_path.insert(new_node.clone()) {
-                continue;
-            }
+fn bron_kerbosch(
+    graph: &Graph,
+    clique: &mut Vec<usize>,
+    mut candidates: Vec<usize>,
+    mut excluded: Vec<usize>,
+    largest: &mut Vec<usize>,
+) {
+    if clique.len() + candidates.len() <= largest.len() {
+        return;
+    }
 
-            let s = possible_path.iter().sorted_unstable().join(",");
+    let Some(&pivot) = candidates
+        .iter()
+        .chain(&excluded)
+        .max_by_key(|&&u| graph.neighbours(u).len())
+    else {
+        *largest = clique.clone();
+        return;
+    };
+
+    for v in candidates
+        .iter()
+        .copied()
+        .filter(|&v| !graph.has_edge(pivot, v))
+        .collect_vec()
+    {
+        let neighbours = graph.neighbours(v);
+
+        clique.push(v);
+        bron_kerbosch(
+            graph,
+            clique,
+            intersect(&candidates, neighbours),
+            intersect(&excluded, neighbours),
+            largest,
+        );
+        clique.pop();
+
+        candidates.retain(|&u| u != v);
+
+        if let Err(i) = excluded.binary_search(&v) {
+            excluded.insert(i, v);
+        }
+    }
+}
 
-            if !visited.insert(s.clone()) {
-                continue;
-            }
+fn part2(input: &str) -> Result<String, ParseError> {
+    let graph = parse(input)?;
 
-            if s.len() > longest_path.len() {
-                longest_path = s;
-            }
+    let mut largest = vec![];
 
-            queue.extend(
-                graph
-                    .get(&new_node)
-                    .unwrap()
-                    .iter()
-                    .map(|node| (possible_path.clone(), node.clone())),
-            )
-        }
-    }
+    bron_kerbosch(
+        &graph,
+        &mut vec![],
+        graph.nodes().collect_vec(),
+        vec![],
+        &mut largest,
+    );
 
-    longest_path
+    Ok(largest
+        .into_iter()
+        .map(|u| graph.label(u))
+        .sorted_unstable()
+        .join(","))
 }
 
-pub fn main() {
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day23");
+
     let mut now = Instant::now();
-    let part1 = part1();
+    let part1 = part1(&input)?;
     let part1_elapsed = now.elapsed();
 
     now = Instant::now();
-    let part2 = part2();
+    let part2 = part2(&input)?;
     let part2_elapsed = now.elapsed();
 
     println!("--- Day 23 ---");
@@ -125,21 +128,79 @@ pub fn main() {
 
     assert_eq!(part1, 926);
     assert_eq!(part2, "az,ed,hz,it,ld,nh,pc,td,ty,ux,wc,yg,zz");
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
     use super::*;
+    use advent_of_code_2024::fuzz;
     use test::Bencher;
 
+    const EXAMPLE: &str = "\
+kh-tc
+qp-kh
+de-cg
+ka-co
+yn-aq
+qp-ub
+cg-tb
+vc-aq
+tb-ka
+wh-tc
+yn-cg
+kh-ub
+ta-co
+de-co
+tc-td
+tb-wq
+wh-td
+ta-ka
+td-qp
+aq-cg
+wq-ub
+ub-vc
+de-ta
+wq-aq
+wq-vc
+wh-yn
+ka-de
+kh-ta
+co-tc
+wh-qp
+tb-vc
+td-yn
+";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(7), part1(EXAMPLE));
+        assert_eq!(Ok("co,de,ka,ta".to_owned()), part2(EXAMPLE));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, part1);
+        fuzz::assert_no_panic(EXAMPLE, part2);
+    }
+
     #[bench]
     fn bench_part1(b: &mut Bencher) {
-        b.iter(part1);
+        let Some(input) = input::bench("day23") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
     }
 
     #[bench]
     fn bench_part2(b: &mut Bencher) {
-        b.iter(part2);
+        let Some(input) = input::bench("day23") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
     }
 }
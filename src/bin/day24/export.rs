@@ -0,0 +1,349 @@
+use crate::{suspects, Circuit, Op};
+use advent_of_code_2024::render::Colour;
+use itertools::Itertools;
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+
+const SUSPECT: Colour = Colour::RED;
+
+impl Op {
+    fn colour(self) -> Colour {
+        match self {
+            Op::And => Colour::BLUE,
+            Op::Or => Colour::GREEN,
+            Op::Xor => Colour::YELLOW,
+        }
+    }
+
+    // The Verilog primitive for the gate.
+    fn primitive(self) -> &'static str {
+        match self {
+            Op::And => "and",
+            Op::Or => "or",
+            Op::Xor => "xor",
+        }
+    }
+}
+
+// Verilog-2005's reserved words, which wires may not be named without escaping them.
+const KEYWORDS: &str = "\
+    always and assign automatic begin buf bufif0 bufif1 case casex casez cell cmos config deassign \
+    default defparam design disable edge else end endcase endconfig endfunction endgenerate \
+    endmodule endprimitive endspecify endtable endtask event for force forever fork function \
+    generate genvar highz0 highz1 if ifnone incdir include initial inout input instance integer \
+    join large liblist library localparam macromodule medium module nand negedge nmos nor \
+    noshowcancelled not notif0 notif1 or output parameter pmos posedge primitive pull0 pull1 \
+    pulldown pullup pulsestyle_ondetect pulsestyle_onevent rcmos real realtime reg release repeat \
+    rnmos rpmos rtran rtranif0 rtranif1 scalared showcancelled signed small specify specparam \
+    strong0 strong1 supply0 supply1 table task time tran tranif0 tranif1 tri tri0 tri1 triand \
+    trior trireg unsigned use uwire vectored wait wand weak0 weak1 while wire wor xnor xor";
+
+// A wire's name as a Verilog identifier. Names that start with a digit or are reserved words are
+// escaped, which takes a backslash before and whitespace after.
+fn identifier(name: &str) -> String {
+    if name.starts_with(|c: char| c.is_ascii_digit())
+        || KEYWORDS.split_ascii_whitespace().any(|word| word == name)
+    {
+        format!("\\{name} ")
+    } else {
+        name.to_owned()
+    }
+}
+
+fn hex(Colour(r, g, b): Colour) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// The circuit as a Graphviz graph, with a node per wire. Gate outputs are filled in by the kind
+/// of gate and outlined in red if they break the pattern of an adder.
+pub fn to_dot(circuit: &Circuit) -> String {
+    let suspects = suspects(circuit);
+    let mut dot = "digraph circuit {\n    rankdir=LR;\n    node [style=filled];\n".to_owned();
+
+    for &(wire, _) in &circuit.initial {
+        let name = circuit.name(wire);
+        let _ = writeln!(dot, "    \"{name}\" [shape=box, fillcolor=\"white\"];");
+    }
+
+    for gate in &circuit.gates {
+        let name = circuit.name(gate.out);
+        let shape = if name.starts_with('z') {
+            "doubleoctagon"
+        } else {
+            "ellipse"
+        };
+        let outline = if suspects.contains(name) {
+            format!(", color=\"{}\", penwidth=3", hex(SUSPECT))
+        } else {
+            String::new()
+        };
+
+        let _ = writeln!(
+            dot,
+            "    \"{name}\" [label=\"{}\\n{name}\", shape={shape}, fillcolor=\"{}\"{outline}];",
+            gate.op.primitive().to_uppercase(),
+            hex(gate.op.colour())
+        );
+    }
+
+    for gate in &circuit.gates {
+        for input in [gate.a, gate.b] {
+            let _ = writeln!(
+                dot,
+                "    \"{}\" -> \"{}\";",
+                circuit.name(input),
+                circuit.name(gate.out)
+            );
+        }
+    }
+
+    dot + "}\n"
+}
+
+/// The circuit as a structural Verilog netlist, module `circuit`, with the wires given values as
+/// its inputs and the `z` wires as its outputs. A `testbench` module drives the inputs with those
+/// values and prints the output as a number, which is part 1, e.g. with Icarus Verilog:
+/// `iverilog -o day24 day24.v && vvp day24`.
+pub fn to_verilog(circuit: &Circuit) -> String {
+    let inputs = circuit
+        .initial
+        .iter()
+        .map(|&(wire, _)| circuit.name(wire))
+        .sorted()
+        .collect_vec();
+    let outputs = circuit
+        .bits('z')
+        .into_iter()
+        .map(|wire| circuit.name(wire))
+        .filter(|name| !inputs.contains(name))
+        .collect_vec();
+    let internal = circuit
+        .wires
+        .iter()
+        .filter(|name| !inputs.contains(name) && !outputs.contains(name))
+        .sorted()
+        .collect_vec();
+
+    let ports = inputs.iter().chain(&outputs).map(|name| identifier(name));
+    let mut verilog = format!("module circuit ({});\n", ports.clone().join(", "));
+
+    // Verilog rejects an empty declaration list, so skip any declaration with nothing in it.
+    if !inputs.is_empty() {
+        let _ = writeln!(
+            verilog,
+            "    input {};",
+            inputs.iter().map(|name| identifier(name)).join(", ")
+        );
+    }
+
+    if !outputs.is_empty() {
+        let _ = writeln!(
+            verilog,
+            "    output {};",
+            outputs.iter().map(|name| identifier(name)).join(", ")
+        );
+    }
+
+    if !internal.is_empty() {
+        let _ = writeln!(
+            verilog,
+            "    wire {};",
+            internal.iter().map(|name| identifier(name)).join(", ")
+        );
+    }
+
+    verilog.push('\n');
+
+    // Wire names are alphanumeric, so instance names with an underscore cannot clash with them.
+    for (g, gate) in circuit.gates.iter().enumerate() {
+        let _ = writeln!(
+            verilog,
+            "    {} gate_{g} ({}, {}, {});",
+            gate.op.primitive(),
+            identifier(circuit.name(gate.out)),
+            identifier(circuit.name(gate.a)),
+            identifier(circuit.name(gate.b))
+        );
+    }
+
+    verilog += "endmodule\n\nmodule testbench;\n";
+
+    for &(wire, value) in circuit
+        .initial
+        .iter()
+        .sorted_by_key(|(wire, _)| circuit.name(*wire))
+    {
+        let _ = writeln!(
+            verilog,
+            "    reg {} = 1'b{};",
+            identifier(circuit.name(wire)),
+            u8::from(value)
+        );
+    }
+
+    if !outputs.is_empty() {
+        let _ = writeln!(
+            verilog,
+            "    wire {};",
+            outputs.iter().map(|name| identifier(name)).join(", ")
+        );
+    }
+
+    let _ = writeln!(
+        verilog,
+        "\n    circuit circuit_under_test ({});\n",
+        ports.map(|name| format!(".{name}({name})")).join(", ")
+    );
+
+    // An empty concatenation is just as invalid, and a circuit with no outputs computes zero.
+    let number = if outputs.is_empty() {
+        "0".to_owned()
+    } else {
+        format!(
+            "{{{}}}",
+            outputs.iter().rev().map(|name| identifier(name)).join(", ")
+        )
+    };
+    let _ = writeln!(verilog, "    initial #1 $display(\"%0d\", {number});");
+
+    verilog + "endmodule\n"
+}
+
+/// Writes to `path` as Verilog if its extension is `v`, or as Graphviz otherwise.
+pub fn save(circuit: &Circuit, path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref();
+
+    let contents = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("v") => to_verilog(circuit),
+        _ => to_dot(circuit),
+    };
+
+    std::fs::write(path, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, part1};
+    use advent_of_code_2024::generate;
+
+    fn unescape(identifier: &str) -> &str {
+        identifier
+            .strip_prefix('\\')
+            .map_or(identifier, str::trim_end)
+    }
+
+    // Reads a netlist from `to_verilog` back into a puzzle input, taking the wires' values from
+    // the testbench.
+    fn parse_verilog(verilog: &str) -> String {
+        let mut initial = vec![];
+        let mut gates = vec![];
+
+        for line in verilog.lines().map(str::trim) {
+            if let Some(reg) = line.strip_prefix("reg ") {
+                let (name, value) = reg.strip_suffix(";").unwrap().split_once(" = 1'b").unwrap();
+                initial.push(format!("{}: {value}\n", unescape(name)));
+                continue;
+            }
+
+            let Some((primitive, rest)) = line.split_once(' ') else {
+                continue;
+            };
+
+            let op = match primitive {
+                "and" => "AND",
+                "or" => "OR",
+                "xor" => "XOR",
+                _ => continue,
+            };
+
+            let (_, terminals) = rest.split_once(" (").unwrap();
+            let (out, a, b) = terminals
+                .strip_suffix(");")
+                .unwrap()
+                .split(", ")
+                .collect_tuple()
+                .unwrap();
+
+            let [out, a, b] = [out, a, b].map(unescape);
+            gates.push(format!("{a} {op} {b} -> {out}\n"));
+        }
+
+        initial.concat() + "\n" + &gates.concat()
+    }
+
+    fn gates<'a>(circuit: &Circuit<'a>) -> Vec<(Op, &'a str, &'a str, &'a str)> {
+        let name = |wire| circuit.name(wire);
+
+        circuit
+            .gates
+            .iter()
+            .map(|gate| (gate.op, name(gate.a), name(gate.b), name(gate.out)))
+            .collect()
+    }
+
+    #[test]
+    fn test_verilog_round_trip() {
+        let input = generate::generate("day24", 10, 0).unwrap();
+        let circuit = parse(&input).unwrap();
+        let verilog = to_verilog(&circuit);
+
+        let round_trip = parse_verilog(&verilog);
+        assert_eq!(gates(&circuit), gates(&parse(&round_trip).unwrap()));
+        assert_eq!(part1(&input), part1(&round_trip));
+
+        assert!(verilog.contains("    input x00, x01, x02,"));
+        assert!(verilog.contains("    output z00, z01, z02,"));
+        assert!(verilog.contains("$display(\"%0d\", {z10, z09, z08,"));
+    }
+
+    // Wires named like reserved words or starting with a digit are escaped.
+    #[test]
+    fn test_verilog_escapes() {
+        let input =
+            "x00: 1\ny00: 1\nreg: 0\n\nx00 AND y00 -> and\nand XOR reg -> 9z\n9z OR reg -> z00\n";
+        let circuit = parse(input).unwrap();
+        let verilog = to_verilog(&circuit);
+
+        let round_trip = parse_verilog(&verilog);
+        assert_eq!(gates(&circuit), gates(&parse(&round_trip).unwrap()));
+        assert_eq!(part1(input), part1(&round_trip));
+
+        assert!(verilog.contains("    reg \\reg  = 1'b0;"));
+        assert!(verilog.contains("    and gate_0 (\\and , x00, y00);"));
+        assert!(verilog.contains("    xor gate_1 (\\9z , \\and , \\reg );"));
+        assert!(verilog.contains(".\\reg (\\reg )"));
+    }
+
+    // Empty declaration lists are invalid Verilog, so they are left out altogether.
+    #[test]
+    fn test_verilog_empty_declarations() {
+        let verilog = to_verilog(&parse("x00: 1\ny00: 0\n\nx00 XOR y00 -> z00\n").unwrap());
+        assert!(verilog.contains("    output z00;"));
+        assert!(!verilog.contains("    wire ;"));
+
+        let verilog = to_verilog(&parse("x00: 1\ny00: 0\n\nx00 XOR y00 -> a00\n").unwrap());
+        assert!(verilog.contains("    wire a00;"));
+        assert!(!verilog.contains("output"));
+        assert!(!verilog.contains("    wire ;"));
+        assert!(verilog.contains("$display(\"%0d\", 0);"));
+    }
+
+    #[test]
+    fn test_dot() {
+        let input = generate::generate("day24", 10, 0).unwrap();
+        let circuit = parse(&input).unwrap();
+        let dot = to_dot(&circuit);
+
+        let highlighted = dot
+            .lines()
+            .filter(|line| line.contains(&format!(", color=\"{}\"", hex(SUSPECT))))
+            .count();
+
+        assert!(dot.starts_with("digraph circuit {\n") && dot.ends_with("}\n"));
+        assert_eq!(8, highlighted);
+        assert_eq!(2 * circuit.gates.len(), dot.matches(" -> ").count());
+        assert!(dot.contains("\"z00\" [label=\"XOR\\nz00\", shape=doubleoctagon"));
+    }
+}
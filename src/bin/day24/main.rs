@@ -0,0 +1,564 @@
+#![cfg_attr(test, feature(test))]
+
+mod export;
+
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::{Input, ParseError};
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::error::Error;
+use std::time::Instant;
+
+const SWAPS: usize = 4;
+
+// Random additions a repaired circuit must get right, on top of a few that run the carry the whole
+// way along.
+const TRIALS: usize = 100;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+enum Op {
+    And,
+    Or,
+    Xor,
+}
+
+impl Op {
+    fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            Op::And => a && b,
+            Op::Or => a || b,
+            Op::Xor => a ^ b,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Gate {
+    a: usize,
+    op: Op,
+    b: usize,
+    out: usize,
+}
+
+// Wires are numbered in order of first appearance.
+#[derive(Clone, Debug)]
+struct Circuit<'a> {
+    wires: Vec<&'a str>,
+    initial: Vec<(usize, bool)>,
+    gates: Vec<Gate>,
+    // The gates reading each wire.
+    consumers: Vec<Vec<usize>>,
+}
+
+impl<'a> Circuit<'a> {
+    fn name(&self, wire: usize) -> &'a str {
+        self.wires[wire]
+    }
+
+    // The wires whose names start with `prefix`, least significant first.
+    fn bits(&self, prefix: char) -> Vec<usize> {
+        (0..self.wires.len())
+            .filter(|&wire| self.name(wire).starts_with(prefix))
+            .sorted_by_key(|&wire| self.name(wire))
+            .collect()
+    }
+
+    // Runs the gates from the wires already set in `values`. A gate whose inputs never get a value,
+    // because they are never driven or are stuck in a loop, leaves its output unset.
+    fn evaluate(&self, values: &mut [Option<bool>]) {
+        let mut queue = (0..values.len())
+            .filter(|&wire| values[wire].is_some())
+            .collect::<VecDeque<_>>();
+
+        while let Some(wire) = queue.pop_front() {
+            for &g in &self.consumers[wire] {
+                let gate = self.gates[g];
+
+                if values[gate.out].is_some() {
+                    continue;
+                }
+
+                if let (Some(a), Some(b)) = (values[gate.a], values[gate.b]) {
+                    values[gate.out] = Some(gate.op.apply(a, b));
+                    queue.push_back(gate.out);
+                }
+            }
+        }
+    }
+
+    // `bits` read as a binary number, if they all have a value.
+    fn number(values: &[Option<bool>], bits: &[usize]) -> Option<u64> {
+        bits.iter()
+            .rev()
+            .try_fold(0, |n, &wire| Some(n << 1 | u64::from(values[wire]?)))
+    }
+
+    // Swaps the outputs of the gates driving `a` and `b`.
+    fn swap(&mut self, a: usize, b: usize) {
+        for gate in &mut self.gates {
+            if gate.out == a {
+                gate.out = b;
+            } else if gate.out == b {
+                gate.out = a;
+            }
+        }
+    }
+}
+
+fn parse<'a>(input: &'a str) -> Result<Circuit<'a>, ParseError> {
+    let input = Input::new(input);
+    let [initial, gates] = input.sections_n()?;
+
+    let mut wires = vec![];
+    let mut ids = HashMap::new();
+    let mut driven = vec![];
+
+    let mut wire = |name: &'a str, drives: bool| -> Result<usize, ParseError> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(input.error(name, "a wire name"));
+        }
+
+        let id = *ids.entry(name).or_insert_with(|| {
+            wires.push(name);
+            driven.push(false);
+            wires.len() - 1
+        });
+
+        if drives && std::mem::replace(&mut driven[id], true) {
+            return Err(input.error(name, "a wire with a single driver"));
+        }
+
+        Ok(id)
+    };
+
+    let initial = input.parse_lines(initial, |line| {
+        let (name, value) = input.split_once(line, ": ")?;
+
+        let value = match value {
+            "0" => false,
+            "1" => true,
+            _ => return Err(input.error(value, "0 or 1")),
+        };
+
+        Ok((wire(name, true)?, value))
+    })?;
+
+    let gates = input.parse_lines(gates, |line| {
+        let (lhs, out) = input.split_once(line, " -> ")?;
+
+        let (a, op, b) = lhs
+            .split(' ')
+            .collect_tuple()
+            .ok_or_else(|| input.error(lhs, "<wire> <gate> <wire>"))?;
+
+        let op = match op {
+            "AND" => Op::And,
+            "OR" => Op::Or,
+            "XOR" => Op::Xor,
+            _ => return Err(input.error(op, "AND, OR or XOR")),
+        };
+
+        Ok(Gate {
+            a: wire(a, false)?,
+            op,
+            b: wire(b, false)?,
+            out: wire(out, true)?,
+        })
+    })?;
+
+    let mut consumers = vec![vec![]; wires.len()];
+
+    for (g, gate) in gates.iter().enumerate() {
+        consumers[gate.a].push(g);
+
+        if gate.b != gate.a {
+            consumers[gate.b].push(g);
+        }
+    }
+
+    Ok(Circuit {
+        wires,
+        initial,
+        gates,
+        consumers,
+    })
+}
+
+fn part1(input: &str) -> Result<u64, ParseError> {
+    let circuit = parse(input)?;
+    let z = circuit.bits('z');
+
+    let mut values = vec![None; circuit.wires.len()];
+
+    for &(wire, value) in &circuit.initial {
+        values[wire] = Some(value);
+    }
+
+    circuit.evaluate(&mut values);
+
+    Circuit::number(&values, &z)
+        .filter(|_| z.len() <= 64)
+        .ok_or_else(|| {
+            Input::new(input).error(input, "at most 64 'z' wires, each of which gets a value")
+        })
+}
+
+// Outputs that break the pattern of a ripple-carry adder, where bit `i` is
+//
+//     z(i) = x(i) XOR y(i) XOR c(i)
+//     c(i + 1) = (x(i) AND y(i)) OR ((x(i) XOR y(i)) AND c(i))
+//
+// except that bit 0 has no carry in, and the last carry out is the highest `z` wire. A swapped
+// output either comes from the wrong kind of gate or feeds the wrong kind of gate.
+fn suspects<'a>(circuit: &Circuit<'a>) -> BTreeSet<&'a str> {
+    let last = circuit.bits('z').last().copied();
+    let is_input = |wire| circuit.name(wire).starts_with(['x', 'y']);
+    let is_first = |wire| matches!(circuit.name(wire), "x00" | "y00");
+
+    let feeds = |wire: usize, op| {
+        circuit.consumers[wire]
+            .iter()
+            .any(|&g| circuit.gates[g].op == op)
+    };
+
+    circuit
+        .gates
+        .iter()
+        .filter(|gate| {
+            let from_inputs = is_input(gate.a) && is_input(gate.b);
+            let first = is_first(gate.a) && is_first(gate.b);
+            let to_z = circuit.name(gate.out).starts_with('z');
+
+            match gate.op {
+                _ if Some(gate.out) == last => gate.op != Op::Or,
+                Op::Xor if from_inputs => !first && !feeds(gate.out, Op::Xor),
+                Op::Xor => !to_z,
+                Op::And => to_z || (!first && !feeds(gate.out, Op::Or)),
+                Op::Or => to_z,
+            }
+        })
+        .map(|gate| circuit.name(gate.out))
+        .collect()
+}
+
+// Whether the circuit adds, on carries that ripple through every bit and on random additions.
+fn adds(circuit: &Circuit, rng: &mut StdRng) -> bool {
+    let (x, y, z) = (circuit.bits('x'), circuit.bits('y'), circuit.bits('z'));
+    let max = (1u64 << x.len()) - 1;
+
+    let trials = [(0, 0), (max, 1), (1, max), (max, max)]
+        .into_iter()
+        .chain((0..TRIALS).map(|_| (rng.gen_range(0..=max), rng.gen_range(0..=max))));
+
+    trials.into_iter().all(|(a, b)| {
+        let mut values = vec![None; circuit.wires.len()];
+
+        for (i, (&x, &y)) in x.iter().zip(&y).enumerate() {
+            values[x] = Some(a >> i & 1 == 1);
+            values[y] = Some(b >> i & 1 == 1);
+        }
+
+        circuit.evaluate(&mut values);
+
+        Circuit::number(&values, &z) == Some(a + b)
+    })
+}
+
+// Tries every way of pairing up `wires`, returning the first whose swaps make the circuit add.
+fn pair_up<'a>(
+    circuit: &mut Circuit<'a>,
+    wires: &mut Vec<usize>,
+    pairs: &mut Vec<(&'a str, &'a str)>,
+    rng: &mut StdRng,
+) -> bool {
+    let Some(a) = wires.pop() else {
+        return adds(circuit, rng);
+    };
+
+    for i in 0..wires.len() {
+        let b = wires.remove(i);
+
+        circuit.swap(a, b);
+        pairs.push((circuit.name(a), circuit.name(b)));
+
+        if pair_up(circuit, wires, pairs, rng) {
+            return true;
+        }
+
+        pairs.pop();
+        circuit.swap(a, b);
+        wires.insert(i, b);
+    }
+
+    wires.push(a);
+
+    false
+}
+
+// The pairs of outputs to swap back to make the circuit a ripple-carry adder.
+fn repair<'a>(circuit: &Circuit<'a>) -> Option<Vec<(&'a str, &'a str)>> {
+    let (x, y, z) = (circuit.bits('x'), circuit.bits('y'), circuit.bits('z'));
+
+    if x.is_empty() || x.len() != y.len() || z.len() != x.len() + 1 || z.len() > 64 {
+        return None;
+    }
+
+    let suspects = suspects(circuit);
+
+    if suspects.len() != 2 * SWAPS {
+        return None;
+    }
+
+    let mut wires = (0..circuit.wires.len())
+        .filter(|&wire| suspects.contains(circuit.name(wire)))
+        .collect_vec();
+    let mut pairs = vec![];
+
+    pair_up(
+        &mut circuit.clone(),
+        &mut wires,
+        &mut pairs,
+        &mut StdRng::seed_from_u64(0),
+    )
+    .then_some(pairs)
+}
+
+fn part2(input: &str) -> Result<String, ParseError> {
+    let circuit = parse(input)?;
+
+    let pairs = repair(&circuit).ok_or_else(|| {
+        let expected = format!("a ripple-carry adder with {SWAPS} pairs of outputs swapped");
+        Input::new(input).error(input, expected)
+    })?;
+
+    Ok(pairs
+        .iter()
+        .flat_map(|&(a, b)| [a, b])
+        .sorted_unstable()
+        .join(","))
+}
+
+pub fn main() -> Result<(), Box<dyn Error>> {
+    let input = input::read("day24");
+
+    let mut now = Instant::now();
+    let part1 = part1(&input)?;
+    let part1_elapsed = now.elapsed();
+
+    now = Instant::now();
+    let part2 = part2(&input)?;
+    let part2_elapsed = now.elapsed();
+
+    println!("--- Day 24 ---");
+    println!("Part 1: {}", part1);
+    println!("Part 2: {}", part2);
+    println!("Part 1 took: {:.2?}", part1_elapsed);
+    println!("Part 2 took: {:.2?}", part2_elapsed);
+
+    // e.g. `cargo run --bin day24 -- day24.dot`, or `day24.v` for Verilog.
+    if let Some(path) = std::env::args().nth(1) {
+        export::save(&parse(&input)?, path)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate test;
+    use super::*;
+    use advent_of_code_2024::{fuzz, generate};
+    use test::Bencher;
+
+    const EXAMPLE1: &str = "\
+x00: 1
+x01: 1
+x02: 1
+y00: 0
+y01: 1
+y02: 0
+
+x00 AND y00 -> z00
+x01 XOR y01 -> z01
+x02 OR y02 -> z02
+";
+
+    const EXAMPLE2: &str = "\
+x00: 1
+x01: 0
+x02: 1
+x03: 1
+x04: 0
+y00: 1
+y01: 1
+y02: 1
+y03: 1
+y04: 1
+
+ntg XOR fgs -> mjb
+y02 OR x01 -> tnw
+kwq OR kpj -> z05
+x00 OR x03 -> fst
+tgd XOR rvg -> z01
+vdt OR tnw -> bfw
+bfw AND frj -> z10
+ffh OR nrd -> bqk
+y00 AND y03 -> djm
+y03 OR y00 -> psh
+bqk OR frj -> z08
+tnw OR fst -> frj
+gnj AND tgd -> z11
+bfw XOR mjb -> z00
+x03 OR x00 -> vdt
+gnj AND wpb -> z02
+x04 AND y00 -> kjc
+djm OR pbc -> qhw
+nrd AND vdt -> hwm
+kjc AND fst -> rvg
+y04 OR y02 -> fgs
+y01 AND x02 -> pbc
+ntg OR kjc -> kwq
+psh XOR fgs -> tgd
+qhw XOR tgd -> z09
+pbc OR djm -> kpj
+x03 XOR y03 -> ffh
+x00 XOR y04 -> ntg
+bfw OR bqk -> z06
+nrd XOR fgs -> wpb
+frj XOR qhw -> z04
+bqk OR frj -> z07
+y03 OR x01 -> nrd
+hwm AND bqk -> z03
+tgd XOR rvg -> z12
+tnw OR pbc -> gnj
+";
+
+    // A `bits` wide ripple-carry adder adding 2^bits - 1 and 1, with the outputs of `swaps`
+    // swapped. Bit `i` has the half sums `s(i)`, the carries through either half `a(i)` and `b(i)`
+    // and the carry out `c(i)`.
+    fn adder(bits: usize, swaps: &[(&str, &str)]) -> String {
+        let mut lines = vec![];
+
+        for i in 0..bits {
+            lines.push(format!("x{i:02}: 1"));
+        }
+
+        for i in 0..bits {
+            lines.push(format!("y{i:02}: {}", u8::from(i == 0)));
+        }
+
+        lines.push(String::new());
+
+        let carry = |i: usize| {
+            if i == bits {
+                format!("z{i:02}")
+            } else {
+                format!("c{i:02}")
+            }
+        };
+
+        lines.push("x00 XOR y00 -> z00".to_owned());
+        lines.push(format!("x00 AND y00 -> {}", carry(1)));
+
+        for i in 1..bits {
+            lines.push(format!("x{i:02} XOR y{i:02} -> s{i:02}"));
+            lines.push(format!("x{i:02} AND y{i:02} -> a{i:02}"));
+            lines.push(format!("s{i:02} XOR c{i:02} -> z{i:02}"));
+            lines.push(format!("c{i:02} AND s{i:02} -> b{i:02}"));
+            lines.push(format!("a{i:02} OR b{i:02} -> {}", carry(i + 1)));
+        }
+
+        let mut input = lines.join("\n") + "\n";
+
+        for &(a, b) in swaps {
+            input = input
+                .replace(&format!("-> {a}\n"), "-> SWAP\n")
+                .replace(&format!("-> {b}\n"), &format!("-> {a}\n"))
+                .replace("-> SWAP\n", &format!("-> {b}\n"));
+        }
+
+        input
+    }
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(4), part1(EXAMPLE1));
+        assert_eq!(Ok(2024), part1(EXAMPLE2));
+    }
+
+    #[test]
+    fn test_adder() {
+        let input = adder(8, &[]);
+
+        assert_eq!(Ok(256), part1(&input));
+        assert!(suspects(&parse(&input).unwrap()).is_empty());
+        assert!(adds(&parse(&input).unwrap(), &mut StdRng::seed_from_u64(0)));
+    }
+
+    #[test]
+    fn test_repair() {
+        // One of each kind of swap: a sum with a carry out, a sum with a carry through the lower
+        // half, a half sum with its carry, and a sum with a carry through the upper half.
+        let swaps = [
+            ("z03", "c04"),
+            ("z06", "b06"),
+            ("s09", "a09"),
+            ("z12", "a12"),
+        ];
+        let input = adder(16, &swaps);
+
+        assert_ne!(Ok(1 << 16), part1(&input));
+        assert_eq!(
+            Ok("a09,a12,b06,c04,s09,z03,z06,z12".to_owned()),
+            part2(&input)
+        );
+
+        assert!(part2(&adder(16, &swaps[..3])).is_err());
+        assert!(part2(EXAMPLE2).is_err());
+    }
+
+    // The generator's smallest adder still has room for four swaps.
+    #[test]
+    fn test_repair_generated() {
+        for seed in 0..10 {
+            let input = generate::generate("day24", 0, seed).unwrap();
+            let wires = part2(&input).unwrap();
+
+            assert_eq!(8, wires.split(',').count(), "{wires}");
+        }
+    }
+
+    #[test]
+    fn test_loops() {
+        assert!(part1("x00: 1\n\nx00 AND a -> b\nb OR x00 -> a\na OR b -> z00\n").is_err());
+        assert!(parse("x00: 1\n\nx00 AND x00 -> x00\n").is_err());
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE1, part1);
+        fuzz::assert_no_panic(EXAMPLE1, part2);
+        fuzz::assert_no_panic(EXAMPLE2, part1);
+        fuzz::assert_no_panic(EXAMPLE2, part2);
+    }
+
+    #[bench]
+    fn bench_part1(b: &mut Bencher) {
+        let Some(input) = input::bench("day24") else {
+            return;
+        };
+
+        b.iter(|| part1(&input));
+    }
+
+    #[bench]
+    fn bench_part2(b: &mut Bencher) {
+        let Some(input) = input::bench("day24") else {
+            return;
+        };
+
+        b.iter(|| part2(&input));
+    }
+}
@@ -0,0 +1,261 @@
+#![cfg_attr(test, feature(test))]
+
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::{Input, ParseError};
+use std::time::Instant;
+
+// A lock's pins or a key's cuts, as the height of each column, and as a mask of its cells.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Schematic {
+    heights: Vec<usize>,
+    mask: u64,
+}
+
+// Locks fill their top row and keys their bottom one, with each column filled in from that end.
+// Every schematic must be the same size, small enough to fit in a mask. Returns the locks, the keys
+// and the room each column has between its two ends.
+fn parse(input: &str) -> Result<(Vec<Schematic>, Vec<Schematic>, usize), ParseError> {
+    let input = Input::new(input);
+
+    let mut locks = vec![];
+    let mut keys = vec![];
+    let mut size = None;
+
+    for section in input.sections() {
+        let rows = section.lines().collect::<Vec<_>>();
+        let (first, last) = (rows[0], rows[rows.len() - 1]);
+
+        if rows.len() < 2 || rows.iter().any(|row| row.len() != first.len()) {
+            return Err(input.error(section, "a rectangular schematic"));
+        }
+
+        if *size.get_or_insert((rows.len(), first.len())) != (rows.len(), first.len()) {
+            return Err(input.error(section, "schematics of the same size"));
+        }
+
+        if rows.len() * first.len() > 64 {
+            return Err(input.error(section, "a schematic of at most 64 cells"));
+        }
+
+        let is_lock = first.bytes().all(|b| b == b'#') && last.bytes().all(|b| b == b'.');
+        let is_key = first.bytes().all(|b| b == b'.') && last.bytes().all(|b| b == b'#');
+
+        if !is_lock && !is_key {
+            return Err(input.error(section, "a lock or a key"));
+        }
+
+        let mut heights = vec![0; first.len()];
+        let mut mask = 0;
+
+        for depth in 0..rows.len() {
+            let x = if is_lock {
+                depth
+            } else {
+                rows.len() - 1 - depth
+            };
+
+            for (y, c) in rows[x].char_indices() {
+                let text = &rows[x][y..y + c.len_utf8()];
+
+                match c {
+                    '#' if heights[y] == depth => {
+                        heights[y] += 1;
+                        mask |= 1u64 << (x * first.len() + y);
+                    }
+                    '#' => return Err(input.error(text, "columns filled in from one end")),
+                    '.' => {}
+                    _ => return Err(input.error(text, "'#' or '.'")),
+                }
+            }
+        }
+
+        // Heights leave out the filled end row.
+        let heights = heights.into_iter().map(|height| height - 1).collect();
+
+        if is_lock {
+            locks.push(Schematic { heights, mask });
+        } else {
+            keys.push(Schematic { heights, mask });
+        }
+    }
+
+    let room = size.map_or(0, |(rows, _)| rows.saturating_sub(2));
+
+    Ok((locks, keys, room))
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Fit {
+    // A key fits a lock if no column holds more than there is room for.
+    Heights,
+    // As the columns are filled from opposite ends, they overlap exactly when the masks do.
+    Mask,
+}
+
+impl Fit {
+    fn fits(self, lock: &Schematic, key: &Schematic, room: usize) -> bool {
+        match self {
+            Fit::Heights => lock
+                .heights
+                .iter()
+                .zip(&key.heights)
+                .all(|(lock, key)| lock + key <= room),
+            Fit::Mask => lock.mask & key.mask == 0,
+        }
+    }
+}
+
+fn part1(input: &str, fit: Fit) -> Result<usize, ParseError> {
+    let (locks, keys, room) = parse(input)?;
+
+    Ok(locks
+        .iter()
+        .map(|lock| keys.iter().filter(|key| fit.fits(lock, key, room)).count())
+        .sum())
+}
+
+pub fn main() -> Result<(), ParseError> {
+    let input = input::read("day25");
+
+    // `cargo run --bin day25 -- heights` compares heights instead of masks.
+    let fit = match std::env::args().nth(1).as_deref() {
+        Some("heights") => Fit::Heights,
+        _ => Fit::Mask,
+    };
+
+    let now = Instant::now();
+    let part1 = part1(&input, fit)?;
+    let part1_elapsed = now.elapsed();
+
+    // There is no second puzzle on the last day.
+    println!("--- Day 25 ---");
+    println!("Part 1: {}", part1);
+    println!("Part 1 took: {:.2?}", part1_elapsed);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate test;
+    use super::*;
+    use advent_of_code_2024::fuzz;
+    use proptest::prelude::*;
+    use test::Bencher;
+
+    const EXAMPLE: &str = "\
+#####
+.####
+.####
+.####
+.#.#.
+.#...
+.....
+
+#####
+##.##
+.#.##
+...##
+...#.
+...#.
+.....
+
+.....
+#....
+#....
+#...#
+#.#.#
+#.###
+#####
+
+.....
+.....
+#.#..
+###..
+###.#
+###.#
+#####
+
+.....
+.....
+.....
+#....
+#.#..
+#.#.#
+#####
+";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(Ok(3), part1(EXAMPLE, Fit::Heights));
+        assert_eq!(Ok(3), part1(EXAMPLE, Fit::Mask));
+    }
+
+    #[test]
+    fn test_heights() {
+        let (locks, keys, room) = parse(EXAMPLE).unwrap();
+
+        assert_eq!(vec![0, 5, 3, 4, 3], locks[0].heights);
+        assert_eq!(vec![5, 0, 2, 1, 3], keys[0].heights);
+        assert_eq!(5, room);
+    }
+
+    #[test]
+    fn test_invalid_schematics() {
+        assert!(parse("#####\n.#.##\n.##..\n.....\n").is_err());
+        assert!(parse("#####\n.....\n\n#####\n.....\n.....\n").is_err());
+        assert!(parse("#####\n#####\n").is_err());
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        fuzz::assert_no_panic(EXAMPLE, |input| part1(input, Fit::Mask));
+    }
+
+    // Locks and keys with random heights, in a 7 by 5 schematic.
+    fn schematics() -> impl Strategy<Value = String> {
+        prop::collection::vec((any::<bool>(), prop::array::uniform5(0..=5usize)), 1..20).prop_map(
+            |schematics| {
+                let sections = schematics.iter().map(|(is_lock, heights)| {
+                    let rows = (0..7).map(|x| {
+                        let depth = if *is_lock { x } else { 6 - x };
+
+                        heights
+                            .iter()
+                            .map(|&height| if depth <= height { '#' } else { '.' })
+                            .collect::<String>()
+                    });
+
+                    rows.collect::<Vec<_>>().join("\n") + "\n"
+                });
+
+                sections.collect::<Vec<_>>().join("\n")
+            },
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn test_mask_matches_heights(input in schematics()) {
+            prop_assert_eq!(part1(&input, Fit::Heights), part1(&input, Fit::Mask));
+        }
+    }
+
+    #[bench]
+    fn bench_part1(b: &mut Bencher) {
+        let Some(input) = input::bench("day25") else {
+            return;
+        };
+
+        b.iter(|| part1(&input, Fit::Mask));
+    }
+
+    #[bench]
+    fn bench_part1_heights(b: &mut Bencher) {
+        let Some(input) = input::bench("day25") else {
+            return;
+        };
+
+        b.iter(|| part1(&input, Fit::Heights));
+    }
+}
@@ -0,0 +1,65 @@
+use std::env;
+use std::io::{self, Write};
+
+use advent_of_code_2024::generate::generate;
+
+const USAGE: &str = "usage: generate <day> [--size <size>] [--seed <seed>]";
+
+// Prints a synthetic input, e.g. `cargo run --release --bin generate -- 6 --size 1000 --seed 7`.
+// Without `--size`, inputs are roughly as big as the real ones.
+fn main() -> io::Result<()> {
+    let mut args = env::args().skip(1);
+
+    let day: u8 = args
+        .next()
+        .expect(USAGE)
+        .parse()
+        .expect("Day is not a number");
+
+    let mut size = None;
+    let mut seed = 0;
+
+    while let Some(flag) = args.next() {
+        let value = args.next().expect(USAGE);
+
+        match flag.as_str() {
+            "--size" => size = Some(value.parse().expect("Size is not a number")),
+            "--seed" => seed = value.parse().expect("Seed is not a number"),
+            _ => panic!("{USAGE}"),
+        }
+    }
+
+    let day = format!("day{day:02}");
+    let size = size.unwrap_or_else(|| default_size(&day));
+    let input = generate(&day, size, seed).unwrap_or_else(|| panic!("No generator for {day}"));
+
+    io::stdout().write_all(input.as_bytes())
+}
+
+// About the size of the real puzzle inputs.
+fn default_size(day: &str) -> usize {
+    match day {
+        "day01" | "day02" => 1000,
+        "day03" => 700,
+        "day04" | "day12" => 140,
+        "day05" => 200,
+        "day06" => 130,
+        "day07" => 850,
+        "day08" | "day15" => 50,
+        "day09" => 19999,
+        "day10" => 53,
+        "day11" => 8,
+        "day13" => 320,
+        "day14" => 500,
+        "day16" | "day20" => 141,
+        "day17" => 16,
+        "day18" => 3450,
+        "day19" => 400,
+        "day21" => 5,
+        "day22" => 1600,
+        "day23" => 520,
+        "day24" => 45,
+        "day25" => 500,
+        _ => 100,
+    }
+}
@@ -0,0 +1,255 @@
+use crate::point::Point;
+
+const BITS: usize = u64::BITS as usize;
+
+/// A set of integers in `0..capacity`, one bit each.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+    capacity: usize,
+}
+
+impl BitSet {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            words: vec![0; capacity.div_ceil(BITS)],
+            capacity,
+        }
+    }
+
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        value < self.capacity && self.words[value / BITS] & (1 << (value % BITS)) != 0
+    }
+
+    /// Adds `value`, returning whether it was newly inserted. Panics if `value` is out of range.
+    pub fn insert(&mut self, value: usize) -> bool {
+        assert!(value < self.capacity, "{value} out of range for BitSet");
+
+        let word = &mut self.words[value / BITS];
+        let bit = 1 << (value % BITS);
+        let inserted = *word & bit == 0;
+        *word |= bit;
+
+        inserted
+    }
+
+    /// Removes `value`, returning whether it was present.
+    pub fn remove(&mut self, value: usize) -> bool {
+        if !self.contains(value) {
+            return false;
+        }
+
+        self.words[value / BITS] &= !(1 << (value % BITS));
+
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Panics if the capacities differ, as do the other set operations.
+    pub fn union_with(&mut self, other: &Self) {
+        self.combine(other, |a, b| a | b);
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.combine(other, |a, b| a & b);
+    }
+
+    pub fn difference_with(&mut self, other: &Self) {
+        self.combine(other, |a, b| a & !b);
+    }
+
+    fn combine(&mut self, other: &Self, op: impl Fn(u64, u64) -> u64) {
+        assert_eq!(self.capacity, other.capacity, "BitSet capacities differ");
+
+        for (a, &b) in self.words.iter_mut().zip(&other.words) {
+            *a = op(*a, b);
+        }
+    }
+
+    /// Members in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+
+                Some(i * BITS + bit)
+            })
+        })
+    }
+}
+
+/// A set of points on a `rows` by `cols` grid, backed by a [`BitSet`] in row-major order.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct BitGrid {
+    rows: usize,
+    cols: usize,
+    bits: BitSet,
+}
+
+impl BitGrid {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            bits: BitSet::new(rows * cols),
+        }
+    }
+
+    pub const fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub const fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Points outside the grid are never contained.
+    pub fn contains(&self, point: Point) -> bool {
+        point
+            .to_index(self.rows, self.cols)
+            .is_some_and(|index| self.bits.contains(index))
+    }
+
+    /// Adds `point`, returning whether it was newly inserted. Panics if `point` is out of bounds.
+    pub fn insert(&mut self, point: Point) -> bool {
+        let index = point
+            .to_index(self.rows, self.cols)
+            .unwrap_or_else(|| panic!("{point:?} out of bounds for BitGrid"));
+
+        self.bits.insert(index)
+    }
+
+    pub fn remove(&mut self, point: Point) -> bool {
+        point
+            .to_index(self.rows, self.cols)
+            .is_some_and(|index| self.bits.remove(index))
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        self.bits.union_with(&other.bits);
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.bits.intersect_with(&other.bits);
+    }
+
+    pub fn difference_with(&mut self, other: &Self) {
+        self.bits.difference_with(&other.bits);
+    }
+
+    /// Members in row-major order, which is also ascending `Point` order.
+    pub fn iter(&self) -> impl Iterator<Item = Point> + '_ {
+        self.bits
+            .iter()
+            .map(|index| Point::from_index(index, self.cols))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitset() {
+        let mut set = BitSet::new(130);
+
+        assert!(set.is_empty());
+        assert!(set.insert(0));
+        assert!(set.insert(64));
+        assert!(set.insert(129));
+        assert!(!set.insert(64));
+        assert!(!set.contains(130));
+
+        assert_eq!(3, set.len());
+        assert_eq!(vec![0, 64, 129], set.iter().collect::<Vec<_>>());
+
+        assert!(set.remove(0));
+        assert!(!set.remove(0));
+        assert_eq!(vec![64, 129], set.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let mut a = BitSet::new(100);
+        let mut b = BitSet::new(100);
+
+        for i in (0..100).step_by(2) {
+            a.insert(i);
+        }
+
+        for i in (0..100).step_by(3) {
+            b.insert(i);
+        }
+
+        let mut union = a.clone();
+        union.union_with(&b);
+        assert_eq!(67, union.len());
+
+        let mut intersection = a.clone();
+        intersection.intersect_with(&b);
+        assert_eq!(
+            (0..100).step_by(6).collect::<Vec<_>>(),
+            intersection.iter().collect::<Vec<_>>()
+        );
+
+        a.difference_with(&b);
+        assert_eq!(33, a.len());
+        assert!(a.iter().all(|i| i % 2 == 0 && i % 3 != 0));
+    }
+
+    #[test]
+    fn test_bit_grid() {
+        let mut grid = BitGrid::new(3, 5);
+
+        assert!(grid.insert(Point::new(2, 1)));
+        assert!(grid.insert(Point::new(0, 4)));
+        assert!(!grid.insert(Point::new(2, 1)));
+
+        assert!(grid.contains(Point::new(0, 4)));
+        assert!(!grid.contains(Point::new(1, 0)));
+        assert!(!grid.contains(Point::new(-1, 4)));
+        assert!(!grid.contains(Point::new(0, 5)));
+
+        assert_eq!(
+            vec![Point::new(0, 4), Point::new(2, 1)],
+            grid.iter().collect::<Vec<_>>()
+        );
+    }
+}
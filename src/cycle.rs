@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A sequence of states that repeats forever after `start` steps with period `length`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step whose state equals the state after `n` steps.
+    pub const fn reduce(self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Brent's algorithm: finds the cycle in constant memory, or returns `None` if `step` ends the
+/// sequence first.
+pub fn brent<S: Clone + Eq>(initial: S, mut step: impl FnMut(&S) -> Option<S>) -> Option<Cycle> {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial)?;
+
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+
+    for _ in 0..length {
+        hare = step(&hare)?;
+    }
+
+    let mut start = 0;
+
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+
+    Some(Cycle { start, length })
+}
+
+/// Remembers every state seen so far, so that each is stepped only once.
+#[derive(Clone, Debug)]
+pub struct History<S> {
+    states: Vec<S>,
+    seen: HashMap<S, usize>,
+}
+
+impl<S: Clone + Eq + Hash> History<S> {
+    /// Steps from `initial` until a state repeats, or returns `None` if `step` ends the sequence
+    /// first.
+    pub fn find_cycle(initial: S, mut step: impl FnMut(&S) -> Option<S>) -> Option<(Self, Cycle)> {
+        let mut history = Self {
+            states: vec![],
+            seen: HashMap::new(),
+        };
+        let mut state = initial;
+
+        loop {
+            if let Some(&start) = history.seen.get(&state) {
+                let length = history.states.len() - start;
+
+                return Some((history, Cycle { start, length }));
+            }
+
+            history.seen.insert(state.clone(), history.states.len());
+            history.states.push(state.clone());
+            state = step(&state)?;
+        }
+    }
+
+    /// States in the order they were reached, the initial state first.
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+}
+
+/// The state after `n` steps, skipping ahead once the states start repeating.
+pub fn state_after<S: Clone + Eq + Hash>(initial: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let mut states = vec![];
+    let mut seen = HashMap::new();
+    let mut state = initial;
+
+    for i in 0..n {
+        if let Some(&start) = seen.get(&state) {
+            let cycle = Cycle {
+                start,
+                length: i - start,
+            };
+
+            return states.swap_remove(cycle.reduce(n));
+        }
+
+        seen.insert(state.clone(), i);
+        states.push(state.clone());
+        state = step(&state);
+    }
+
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3, 10, 19, 34, 9, then 0, 1, 2, 5, 26, 21, 32 repeating
+    fn square_mod(n: &u64) -> Option<u64> {
+        Some((n * n + 1) % 41)
+    }
+
+    #[test]
+    fn test_brent_and_history_agree() {
+        let expected = Cycle {
+            start: 5,
+            length: 7,
+        };
+
+        assert_eq!(Some(expected), brent(3, square_mod));
+
+        let (history, cycle) = History::find_cycle(3, square_mod).unwrap();
+        assert_eq!(expected, cycle);
+        assert_eq!([3, 10, 19, 34, 9, 0, 1, 2, 5, 26, 21, 32], history.states());
+    }
+
+    #[test]
+    fn test_terminating_sequence() {
+        let step = |n: &u32| n.checked_sub(1);
+
+        assert_eq!(None, brent(10, step));
+        assert!(History::find_cycle(10, step).is_none());
+    }
+
+    #[test]
+    fn test_state_after() {
+        let step = |n: &u64| square_mod(n).unwrap();
+
+        for n in 0..50 {
+            let mut expected = 3;
+
+            for _ in 0..n {
+                expected = step(&expected);
+            }
+
+            assert_eq!(expected, state_after(3, step, n));
+        }
+
+        assert_eq!(1, state_after(3, step, 1_000_000_000));
+    }
+}
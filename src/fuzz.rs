@@ -0,0 +1,107 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// How many mutated and how many random inputs [`inputs`] makes.
+const CASES: usize = 100;
+
+/// How long a solver may take on one input before it is considered stuck.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Characters that are not part of any puzzle, mixed in with the example's own.
+const STRANGE: [char; 8] = ['\0', '\t', '\r', '-', ',', ':', '9', 'é'];
+
+/// Malformed variants of `example`: the empty input, every line-aligned truncation and a sample of
+/// others, copies with a few characters replaced, removed or inserted, and short runs of random
+/// characters.
+pub fn inputs(example: &str, seed: u64) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let chars = example.chars().collect::<Vec<_>>();
+
+    let alphabet = chars.iter().chain(&STRANGE).copied().collect::<Vec<_>>();
+
+    let mut inputs = vec![];
+
+    for end in 0..chars.len() {
+        if end == 0 || chars[end] == '\n' || rng.gen_ratio(1, 8) {
+            inputs.push(chars[..end].iter().collect());
+        }
+    }
+
+    for _ in 0..CASES {
+        let mut mutated = chars.clone();
+
+        for _ in 0..rng.gen_range(1..=3) {
+            let i = rng.gen_range(0..=mutated.len());
+            let c = *alphabet.choose(&mut rng).unwrap();
+
+            match rng.gen_range(0..3) {
+                0 if i < mutated.len() => mutated[i] = c,
+                1 if i < mutated.len() => {
+                    mutated.remove(i);
+                }
+                _ => mutated.insert(i, c),
+            }
+        }
+
+        inputs.push(mutated.into_iter().collect());
+    }
+
+    for _ in 0..CASES {
+        let len = rng.gen_range(0..64);
+
+        inputs.push(
+            (0..len)
+                .map(|_| *alphabet.choose(&mut rng).unwrap())
+                .collect(),
+        );
+    }
+
+    inputs
+}
+
+/// Runs `solve` on each of [`inputs`], panicking with the offending input if it panics or gets
+/// stuck. What it returns is up to `solve`, which is expected to report an error.
+pub fn assert_no_panic<T: Send + 'static>(example: &str, solve: fn(&str) -> T) {
+    for input in inputs(example, 0) {
+        let (sender, receiver) = mpsc::channel();
+        let owned = input.clone();
+
+        thread::spawn(move || {
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| solve(&owned)));
+            let _ = sender.send(outcome.is_ok());
+        });
+
+        match receiver.recv_timeout(TIMEOUT) {
+            Ok(true) => {}
+            Ok(false) => panic!("Panicked on {input:?}"),
+            Err(_) => panic!("Still running after {TIMEOUT:?} on {input:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inputs() {
+        let example = "1 2\n3 4\n";
+        let inputs = inputs(example, 0);
+
+        assert_eq!(inputs, super::inputs(example, 0));
+        assert!(inputs.iter().any(String::is_empty));
+        assert!(inputs.contains(&"1 2".to_owned()));
+        assert!(inputs.len() >= 2 * CASES);
+    }
+
+    #[test]
+    #[should_panic(expected = "Panicked on")]
+    fn test_reports_panics() {
+        assert_no_panic("12\n", |input| input.trim().parse::<u8>().unwrap());
+    }
+}
@@ -0,0 +1,801 @@
+use crate::bitset::BitGrid;
+use crate::grid::Grid;
+use crate::point::{Direction, Point};
+use crate::search;
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashSet, VecDeque};
+
+/// A valid puzzle input for `day` (e.g. `"day06"`), reproducible from `seed`, or `None` if the day
+/// has no generator.
+///
+/// `size` scales the part of the input that grows with the puzzle: the side of the grid for grid
+/// days and the number of lines, stones, digits or bytes otherwise. Days whose puzzle fixes a
+/// limit clamp it, as noted on each generator.
+pub fn generate(day: &str, size: usize, seed: u64) -> Option<String> {
+    let rng = &mut StdRng::seed_from_u64(seed);
+    let size = size.max(1);
+
+    let input = match day {
+        "day01" => day01(rng, size),
+        "day02" => day02(rng, size),
+        "day03" => day03(rng, size),
+        "day04" => day04(rng, size),
+        "day05" => day05(rng, size),
+        "day06" => day06(rng, size),
+        "day07" => day07(rng, size),
+        "day08" => day08(rng, size),
+        "day09" => day09(rng, size),
+        "day10" => day10(rng, size),
+        "day11" => day11(rng, size),
+        "day12" => day12(rng, size),
+        "day13" => day13(rng, size),
+        "day14" => day14(rng, size),
+        "day15" => day15(rng, size),
+        "day16" => day16(rng, size),
+        "day17" => day17(rng, size),
+        "day18" => day18(rng, size),
+        "day19" => day19(rng, size),
+        "day20" => day20(rng, size),
+        "day21" => day21(rng, size),
+        "day22" => day22(rng, size),
+        "day23" => day23(rng, size),
+        "day24" => day24(rng, size),
+        "day25" => day25(rng, size),
+        _ => return None,
+    };
+
+    Some(input)
+}
+
+fn lines(lines: impl IntoIterator<Item = String>) -> String {
+    lines.into_iter().map(|line| line + "\n").collect()
+}
+
+// `size` lines. Half of the right list repeats values from the left so that part 2 finds matches.
+fn day01(rng: &mut StdRng, size: usize) -> String {
+    let mut left = Vec::with_capacity(size);
+
+    lines((0..size).map(|_| {
+        left.push(rng.gen_range(10000..100000));
+
+        let right = if rng.gen_bool(0.5) {
+            *left.choose(rng).unwrap()
+        } else {
+            rng.gen_range(10000..100000)
+        };
+
+        format!("{}   {right}", left.last().unwrap())
+    }))
+}
+
+// `size` reports, mostly trending one way with the occasional bad level.
+fn day02(rng: &mut StdRng, size: usize) -> String {
+    lines((0..size).map(|_| {
+        let sign = if rng.gen_bool(0.5) { 1 } else { -1 };
+        let mut levels = vec![rng.gen_range(10..90)];
+
+        for _ in 1..rng.gen_range(5..=8) {
+            let last = *levels.last().unwrap();
+
+            levels.push(if rng.gen_bool(0.9) {
+                last + sign * rng.gen_range(0..=4)
+            } else {
+                last - sign * rng.gen_range(1..=3)
+            });
+        }
+
+        levels.iter().join(" ")
+    }))
+}
+
+// `size` instructions, valid or corrupted.
+fn day03(rng: &mut StdRng, size: usize) -> String {
+    const NOISE: [&str; 8] = [
+        "do()",
+        "don't()",
+        "mul(4*",
+        "xmul[3,7]",
+        "%&mul(2,4)",
+        "select()",
+        "mul ( 2 , 4 )",
+        "what()",
+    ];
+
+    let mut memory = String::new();
+
+    for _ in 0..size {
+        if rng.gen_bool(0.3) {
+            memory += &format!("mul({},{})", rng.gen_range(1..1000), rng.gen_range(1..1000));
+        } else {
+            memory += NOISE.choose(rng).unwrap();
+        }
+    }
+
+    memory + "\n"
+}
+
+// A `size` by `size` word search.
+fn day04(rng: &mut StdRng, size: usize) -> String {
+    let letters = ['X', 'M', 'A', 'S'];
+
+    Grid::from_fn(size, size, |_| *letters.choose(rng).unwrap()).to_string()
+}
+
+// `size` updates of 49 pages, with a rule for every pair of pages.
+fn day05(rng: &mut StdRng, size: usize) -> String {
+    let mut pages = (10..100).collect_vec();
+    pages.shuffle(rng);
+    pages.truncate(49);
+
+    let mut rules = pages
+        .iter()
+        .tuple_combinations()
+        .map(|(before, after)| format!("{before}|{after}"))
+        .collect_vec();
+    rules.shuffle(rng);
+
+    let updates = (0..size).map(|_| {
+        let length = *[5, 7, 9, 11, 13, 21].choose(rng).unwrap();
+
+        pages.choose_multiple(rng, length).join(",")
+    });
+
+    lines(rules) + "\n" + &lines(updates)
+}
+
+// A `size` by `size` lab, redrawn until the guard walks out of it.
+fn day06(rng: &mut StdRng, size: usize) -> String {
+    let size = size.max(2);
+
+    loop {
+        let mut lab = Grid::from_fn(size, size, |_| if rng.gen_bool(0.045) { '#' } else { '.' });
+        let guard = Point::new(
+            rng.gen_range(0..size as isize),
+            rng.gen_range(0..size as isize),
+        );
+        lab[guard] = '^';
+
+        let mut seen = HashSet::new();
+        let mut state = (guard, Direction::Up);
+
+        let leaves = loop {
+            if !seen.insert(state) {
+                break false;
+            }
+
+            let (pos, dir) = state;
+
+            state = match lab.get(pos + dir) {
+                None => break true,
+                Some('#') => (pos, dir.turn_right()),
+                Some(_) => (pos + dir, dir),
+            };
+        };
+
+        if leaves {
+            return lab.to_string();
+        }
+    }
+}
+
+// `size` equations, most built from random operators and the rest with random targets.
+fn day07(rng: &mut StdRng, size: usize) -> String {
+    lines((0..size).map(|_| {
+        let numbers = (0..rng.gen_range(3..=12))
+            .map(|_| rng.gen_range(1..100u64))
+            .collect_vec();
+
+        let target = numbers[1..]
+            .iter()
+            .try_fold(numbers[0], |acc, &n| match rng.gen_range(0..3) {
+                0 => acc.checked_add(n),
+                1 => acc.checked_mul(n),
+                _ => format!("{acc}{n}").parse().ok(),
+            })
+            .filter(|&target| target < 1 << 62 && rng.gen_bool(0.7))
+            .unwrap_or_else(|| rng.gen_range(1..1_000_000_000));
+
+        format!("{target}: {}", numbers.iter().join(" "))
+    }))
+}
+
+// A `size` by `size` map with every frequency used about once per 600 cells.
+fn day08(rng: &mut StdRng, size: usize) -> String {
+    const FREQUENCIES: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+    let mut map = Grid::new(size, size, '.');
+
+    for frequency in FREQUENCIES.chars() {
+        for _ in 0..(size * size / 600).max(2) {
+            let pos = Point::new(
+                rng.gen_range(0..size as isize),
+                rng.gen_range(0..size as isize),
+            );
+            map[pos] = frequency;
+        }
+    }
+
+    map.to_string()
+}
+
+// A disk map of `size` digits, rounded up to end on a file.
+fn day09(rng: &mut StdRng, size: usize) -> String {
+    let digits = (0..size | 1).map(|i| {
+        let length = if i % 2 == 0 {
+            rng.gen_range(1..=9)
+        } else {
+            rng.gen_range(0..=9)
+        };
+
+        char::from(b'0' + length)
+    });
+
+    digits.collect::<String>() + "\n"
+}
+
+// A `size` by `size` map rising diagonally, with enough noise to make the trails branch.
+fn day10(rng: &mut StdRng, size: usize) -> String {
+    let map = Grid::from_fn(size, size, |pos| {
+        let noise = if rng.gen_bool(0.75) {
+            0
+        } else {
+            rng.gen_range(1..=2)
+        };
+
+        char::from(b'0' + ((pos.x + pos.y + noise) % 10) as u8)
+    });
+
+    map.to_string()
+}
+
+// `size` stones.
+fn day11(rng: &mut StdRng, size: usize) -> String {
+    (0..size).map(|_| rng.gen_range(0..1_000_000)).join(" ") + "\n"
+}
+
+// A `size` by `size` garden, where plots tend to copy the plant above or to the left of them.
+fn day12(rng: &mut StdRng, size: usize) -> String {
+    let mut garden = Grid::new(size, size, 'A');
+
+    for pos in garden.points() {
+        garden[pos] = if pos.x > 0 && rng.gen_bool(0.6) {
+            garden[pos + Direction::Up]
+        } else if pos.y > 0 && rng.gen_bool(0.6) {
+            garden[pos + Direction::Left]
+        } else {
+            rng.gen_range('A'..='Z')
+        };
+    }
+
+    garden.to_string()
+}
+
+// `size` claw machines, half of which can win their prize.
+fn day13(rng: &mut StdRng, size: usize) -> String {
+    let machines = (0..size).map(|_| {
+        let [ax, ay, bx, by] = [(); 4].map(|_| rng.gen_range(10..100));
+
+        let (x, y) = if rng.gen_bool(0.5) {
+            let (a, b) = (rng.gen_range(1..=100), rng.gen_range(1..=100));
+            (ax * a + bx * b, ay * a + by * b)
+        } else {
+            (rng.gen_range(1000..20000), rng.gen_range(1000..20000))
+        };
+
+        format!("Button A: X+{ax}, Y+{ay}\nButton B: X+{bx}, Y+{by}\nPrize: X={x}, Y={y}\n")
+    });
+
+    machines.collect_vec().join("\n")
+}
+
+// `size` robots in the puzzle's 101 by 103 space. At a random second, up to 60% of them draw a
+// framed tree, 31 wide and 33 tall; the rest are scattered at random throughout.
+fn day14(rng: &mut StdRng, size: usize) -> String {
+    const WIDTH: i64 = 101;
+    const HEIGHT: i64 = 103;
+
+    let seconds = rng.gen_range(0..WIDTH * HEIGHT);
+    let (left, top) = (rng.gen_range(0..WIDTH - 31), rng.gen_range(0..HEIGHT - 33));
+
+    let mut picture = (0..33i64)
+        .cartesian_product(0..31i64)
+        .filter(|&(y, x)| {
+            let frame = y == 0 || y == 32 || x == 0 || x == 30;
+            let tree = (2..=24).contains(&y) && (x - 15).abs() <= (y - 2) / 2 % 4 + (y - 2) / 8 * 2;
+            let trunk = (25..=28).contains(&y) && (13..=17).contains(&x);
+
+            frame || tree || trunk
+        })
+        .map(|(y, x)| (left + x, top + y))
+        .collect_vec();
+    picture.shuffle(rng);
+    picture.truncate(size * 3 / 5);
+
+    lines((0..size).map(|i| {
+        let (x, y) = picture
+            .get(i)
+            .copied()
+            .unwrap_or_else(|| (rng.gen_range(0..WIDTH), rng.gen_range(0..HEIGHT)));
+        let (dx, dy) = (rng.gen_range(-99..=99), rng.gen_range(-99..=99));
+
+        format!(
+            "p={},{} v={dx},{dy}",
+            (x - dx * seconds).rem_euclid(WIDTH),
+            (y - dy * seconds).rem_euclid(HEIGHT)
+        )
+    }))
+}
+
+fn on_border(pos: Point, size: usize) -> bool {
+    pos.x == 0 || pos.y == 0 || pos.x == size as isize - 1 || pos.y == size as isize - 1
+}
+
+// A `size` by `size` warehouse and `8 * size * size` moves.
+fn day15(rng: &mut StdRng, size: usize) -> String {
+    let size = size.max(4);
+
+    let mut warehouse = Grid::from_fn(size, size, |pos| {
+        if on_border(pos, size) || rng.gen_bool(0.05) {
+            '#'
+        } else if rng.gen_bool(0.25) {
+            'O'
+        } else {
+            '.'
+        }
+    });
+
+    warehouse[Point::new(size as isize / 2, size as isize / 2)] = '@';
+
+    let moves = (0..8 * size * size)
+        .map(|_| *['^', '>', 'v', '<'].choose(rng).unwrap())
+        .chunks(1000)
+        .into_iter()
+        .map(|line| line.collect::<String>())
+        .collect_vec();
+
+    warehouse.to_string() + "\n" + &lines(moves)
+}
+
+// A perfect maze over the odd cells of a `size` by `size` grid, carved by a randomised depth
+// first search from `start`. Returns the open cells and each cell's parent in the search tree.
+fn maze(rng: &mut StdRng, size: usize, start: Point) -> (Grid<bool>, Grid<Option<Point>>) {
+    let mut open = Grid::new(size, size, false);
+    let mut parent = Grid::new(size, size, None);
+    let mut stack = vec![start];
+
+    open[start] = true;
+
+    while let Some(&cell) = stack.last() {
+        let unvisited = Direction::ALL
+            .map(|dir| (cell + dir.offset() * 2, dir))
+            .into_iter()
+            .filter(|&(next, _)| {
+                next.in_bounds(size, size) && !on_border(next, size) && !open[next]
+            })
+            .collect_vec();
+
+        let Some(&(next, dir)) = unvisited.choose(rng) else {
+            stack.pop();
+            continue;
+        };
+
+        open[cell + dir] = true;
+        open[next] = true;
+        parent[next] = Some(cell);
+        stack.push(next);
+    }
+
+    (open, parent)
+}
+
+fn odd(size: usize) -> usize {
+    size.max(5) | 1
+}
+
+// A `size` by `size` maze, rounded up to odd, with a tenth of the inner walls knocked down to add
+// loops. Every open cell is reachable, so the end can always be reached from the start.
+fn day16(rng: &mut StdRng, size: usize) -> String {
+    let size = odd(size);
+    let start = Point::new(size as isize - 2, 1);
+    let end = Point::new(1, size as isize - 2);
+
+    let (mut open, _) = maze(rng, size, start);
+
+    for pos in open.points().collect_vec() {
+        if !on_border(pos, size) && (pos.x + pos.y) % 2 == 1 && rng.gen_bool(0.1) {
+            open[pos] = true;
+        }
+    }
+
+    let mut maze = open.map(|_, &open| if open { '.' } else { '#' });
+    maze[start] = 'S';
+    maze[end] = 'E';
+
+    maze.to_string()
+}
+
+// The puzzle's program shape with random constants, and an A register of `size` octal digits, at
+// most 16. Constants are redrawn until some A makes the program output itself.
+fn day17(rng: &mut StdRng, size: usize) -> String {
+    loop {
+        let (k1, k2) = (rng.gen_range(0..8), rng.gen_range(0..8));
+        let program = [2, 4, 1, k1, 7, 5, 1, k2, 4, 3, 0, 3, 5, 5, 3, 0];
+
+        // bst A, bxl k1, cdv B, bxl k2, bxc, adv 3, out B, jnz 0.
+        let first_output = |a: u64| {
+            let b = (a % 8) ^ k1;
+            (b ^ k2 ^ (a >> b)) % 8
+        };
+
+        // Builds A three bits at a time, matching the program from its end.
+        let mut queue = VecDeque::from([(0u64, 0)]);
+        let mut solvable = false;
+
+        while let Some((a, matched)) = queue.pop_front() {
+            if matched == program.len() {
+                solvable = true;
+                break;
+            }
+
+            for tail in 0..8 {
+                let next = a * 8 + tail;
+
+                if next != 0 && first_output(next) == program[program.len() - 1 - matched] {
+                    queue.push_back((next, matched + 1));
+                }
+            }
+        }
+
+        if solvable {
+            let a = rng.gen_range(1u64 << (3 * (size.min(16) - 1))..1 << (3 * size.min(16)));
+
+            return format!(
+                "Register A: {a}\nRegister B: 0\nRegister C: 0\n\nProgram: {}\n",
+                program.iter().join(",")
+            );
+        }
+    }
+}
+
+// `size` bytes falling into the puzzle's 71 by 71 memory space. The first 1024 bytes always leave
+// a way out, and there are always enough bytes to block it.
+fn day18(rng: &mut StdRng, size: usize) -> String {
+    const N: usize = 71;
+    const SAFE: usize = 1024;
+
+    let start = Point::new(0, 0);
+    let end = Point::new(N as isize - 1, N as isize - 1);
+
+    // A staircase from corner to corner that the first bytes avoid.
+    let mut path = HashSet::from([start]);
+    let mut pos = start;
+
+    while pos != end {
+        let dir = if pos.x == end.x || (pos.y != end.y && rng.gen_bool(0.5)) {
+            Direction::Right
+        } else {
+            Direction::Down
+        };
+
+        pos += dir;
+        path.insert(pos);
+    }
+
+    let (mut bytes, mut rest): (Vec<_>, Vec<_>) = (0..N * N)
+        .map(|index| Point::from_index(index, N))
+        .filter(|&pos| pos != start && pos != end)
+        .partition(|pos| !path.contains(pos));
+
+    bytes.shuffle(rng);
+    rest.extend(bytes.drain(SAFE..));
+    rest.shuffle(rng);
+    bytes.extend(rest);
+
+    let blocked = |count: usize| {
+        let mut corrupted = BitGrid::new(N, N);
+
+        for &pos in &bytes[..count] {
+            corrupted.insert(pos);
+        }
+
+        search::bfs([start], |&u| {
+            u.neighbours()
+                .into_iter()
+                .filter(|&v| v.in_bounds(N, N) && !corrupted.contains(v))
+        })
+        .cost(&end)
+        .is_none()
+    };
+
+    let cut = (SAFE..=bytes.len())
+        .collect_vec()
+        .partition_point(|&count| !blocked(count))
+        + SAFE;
+
+    bytes.truncate(size.clamp(cut, bytes.len()));
+
+    lines(bytes.iter().map(|pos| format!("{},{}", pos.y, pos.x)))
+}
+
+// About 440 patterns and `size` designs, half of which are made from the patterns.
+fn day19(rng: &mut StdRng, size: usize) -> String {
+    fn random_stripes(rng: &mut StdRng, length: usize) -> String {
+        (0..length)
+            .map(|_| *['w', 'u', 'b', 'r', 'g'].choose(rng).unwrap())
+            .collect()
+    }
+
+    let patterns = (0..440)
+        .map(|_| {
+            let length = rng.gen_range(1..=8);
+            random_stripes(rng, length)
+        })
+        .sorted()
+        .dedup()
+        .collect_vec();
+
+    let designs = (0..size).map(|_| {
+        let length = rng.gen_range(20..=60);
+
+        if rng.gen_bool(0.5) {
+            random_stripes(rng, length)
+        } else {
+            let mut design = String::new();
+
+            while design.len() < length {
+                design += patterns.choose(rng).unwrap();
+            }
+
+            design
+        }
+    });
+
+    patterns.join(", ") + "\n\n" + &lines(designs)
+}
+
+// A `size` by `size` racetrack, rounded up to odd: the path between opposite corners of a perfect
+// maze, with the rest of the maze walled up. No two parts of the track touch, so there is a single
+// way through.
+fn day20(rng: &mut StdRng, size: usize) -> String {
+    let size = odd(size);
+    let start = Point::new(1, 1);
+    let end = Point::new(size as isize - 2, size as isize - 2);
+
+    let (_, parent) = maze(rng, size, start);
+
+    let mut track = Grid::new(size, size, '#');
+    let mut pos = end;
+
+    while let Some(prev) = parent[pos] {
+        track[pos] = '.';
+        track[Point::new((pos.x + prev.x) / 2, (pos.y + prev.y) / 2)] = '.';
+        pos = prev;
+    }
+
+    track[start] = 'S';
+    track[end] = 'E';
+
+    track.to_string()
+}
+
+// `size` door codes of three digits, not all zero, and an 'A'.
+fn day21(rng: &mut StdRng, size: usize) -> String {
+    lines((0..size).map(|_| format!("{:03}A", rng.gen_range(1..1000))))
+}
+
+// `size` buyers.
+fn day22(rng: &mut StdRng, size: usize) -> String {
+    lines((0..size).map(|_| rng.gen_range(1..1 << 24).to_string()))
+}
+
+// `size` computers, between 26 and 676, with about 13 connections each. 13 of them form a LAN
+// party far larger than any clique a random network of this density would hold.
+fn day23(rng: &mut StdRng, size: usize) -> String {
+    let mut names = ('a'..='z')
+        .cartesian_product('a'..='z')
+        .map(|(a, b)| format!("{a}{b}"))
+        .collect_vec();
+
+    names.shuffle(rng);
+    names.truncate(size.clamp(26, 676));
+
+    let mut edges = names[..13]
+        .iter()
+        .tuple_combinations()
+        .map(|(a, b)| (a.min(b), a.max(b)))
+        .collect::<HashSet<_>>();
+
+    while edges.len() < names.len() * 13 / 2 {
+        let pair = names.choose_multiple(rng, 2).collect_vec();
+        edges.insert((pair[0].min(pair[1]), pair[0].max(pair[1])));
+    }
+
+    let mut edges = edges
+        .into_iter()
+        .sorted()
+        .map(|(a, b)| {
+            if rng.gen_bool(0.5) {
+                format!("{a}-{b}")
+            } else {
+                format!("{b}-{a}")
+            }
+        })
+        .collect_vec();
+
+    edges.shuffle(rng);
+
+    lines(edges)
+}
+
+// A ripple-carry adder of `size` bits, between 5 and 63, with random inputs and the outputs of
+// four pairs of gates swapped, each pair within one bit. The swaps are of the kinds found in the
+// puzzle, which put a gate's output on the wrong kind of gate.
+fn day24(rng: &mut StdRng, size: usize) -> String {
+    // Four swaps between the bits that have both a carry in and a carry out take six bits.
+    let bits = size.clamp(6, 63);
+
+    let mut names = ('a'..='w')
+        .cartesian_product('a'..='z')
+        .cartesian_product('a'..='z')
+        .map(|((a, b), c)| format!("{a}{b}{c}"))
+        .collect_vec();
+    names.shuffle(rng);
+
+    let mut names = names.into_iter();
+    let mut name = || names.next().unwrap();
+
+    // The half sum, the carries through either half and the carry out of each bit.
+    let wires = (0..bits)
+        .map(|i| {
+            let carry = if i == bits - 1 {
+                format!("z{bits:02}")
+            } else {
+                name()
+            };
+
+            [name(), name(), name(), carry]
+        })
+        .collect_vec();
+
+    let mut gates = vec![
+        ["x00", "XOR", "y00", "z00"].map(str::to_owned),
+        ["x00", "AND", "y00", &wires[0][3]].map(str::to_owned),
+    ];
+
+    for i in 1..bits {
+        let [s, a, b, c] = wires[i].clone();
+        let (x, y, z, carry) = (
+            format!("x{i:02}"),
+            format!("y{i:02}"),
+            format!("z{i:02}"),
+            wires[i - 1][3].clone(),
+        );
+
+        gates.push([x.clone(), "XOR".to_owned(), y.clone(), s.clone()]);
+        gates.push([x, "AND".to_owned(), y, a.clone()]);
+        gates.push([s.clone(), "XOR".to_owned(), carry.clone(), z]);
+        gates.push([carry, "AND".to_owned(), s, b.clone()]);
+        gates.push([a, "OR".to_owned(), b, c]);
+    }
+
+    let swapped = (1..bits - 1).collect_vec();
+    let swapped = swapped.choose_multiple(rng, 4).collect_vec();
+    assert_eq!(4, swapped.len(), "four bits to swap wires in");
+
+    for i in swapped {
+        let [s, a, b, c] = &wires[*i];
+        let z = format!("z{i:02}");
+
+        let (p, q) = *[(&z, c), (&z, b), (s, a), (&z, a)].choose(rng).unwrap();
+        let (p, q) = (p.clone(), q.clone());
+
+        for gate in &mut gates {
+            if gate[3] == p {
+                gate[3] = q.clone();
+            } else if gate[3] == q {
+                gate[3] = p.clone();
+            }
+        }
+    }
+
+    let initial = ['x', 'y']
+        .into_iter()
+        .cartesian_product(0..bits)
+        .map(|(c, i)| format!("{c}{i:02}: {}", rng.gen_range(0..=1)))
+        .collect_vec();
+
+    let mut gates = gates
+        .into_iter()
+        .map(|[a, op, b, out]| {
+            if rng.gen_bool(0.5) {
+                format!("{a} {op} {b} -> {out}")
+            } else {
+                format!("{b} {op} {a} -> {out}")
+            }
+        })
+        .collect_vec();
+    gates.shuffle(rng);
+
+    lines(initial) + "\n" + &lines(gates)
+}
+
+// `size` schematics, each a lock or a key with random heights.
+fn day25(rng: &mut StdRng, size: usize) -> String {
+    let schematics = (0..size).map(|_| {
+        let is_lock = rng.gen_bool(0.5);
+        let heights = [(); 5].map(|_| rng.gen_range(0..=5));
+
+        let rows = (0..7).map(|x| {
+            let depth = if is_lock { x } else { 6 - x };
+
+            heights
+                .iter()
+                .map(|&height| if depth <= height { '#' } else { '.' })
+                .collect::<String>()
+        });
+
+        lines(rows)
+    });
+
+    schematics.collect_vec().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reproducible() {
+        for day in ["day01", "day06", "day17", "day23"] {
+            assert_eq!(generate(day, 30, 7), generate(day, 30, 7));
+            assert_ne!(generate(day, 30, 7), generate(day, 30, 8));
+        }
+
+        assert_eq!(None, generate("day26", 30, 7));
+    }
+
+    #[test]
+    fn test_smallest_adder() {
+        for seed in 0..10 {
+            let input = generate("day24", 0, seed).unwrap();
+
+            assert!(input.contains("x05: ") && !input.contains("x06: "));
+            assert!(input.contains("-> z06\n"));
+        }
+    }
+
+    #[test]
+    fn test_mazes() {
+        for seed in 0..10 {
+            let maze = Grid::parse(&generate("day16", 21, seed).unwrap()).unwrap();
+            let start = maze.position(|&c| c == 'S').unwrap();
+            let end = maze.position(|&c| c == 'E').unwrap();
+
+            let paths = search::bfs([start], |&u| {
+                u.neighbours()
+                    .into_iter()
+                    .filter(|&v| maze.get(v).is_some_and(|&c| c != '#'))
+            });
+
+            assert!(paths.cost(&end).is_some());
+        }
+    }
+
+    #[test]
+    fn test_racetracks() {
+        for seed in 0..10 {
+            let track = Grid::parse(&generate("day20", 21, seed).unwrap()).unwrap();
+            let on_track = |pos: Point| track.get(pos).is_some_and(|&c| c != '#');
+
+            for (pos, &c) in track.iter().filter(|&(pos, _)| on_track(pos)) {
+                let neighbours = pos.neighbours().into_iter().filter(|&v| on_track(v));
+                let expected = if c == '.' { 2 } else { 1 };
+
+                assert_eq!(expected, neighbours.count(), "{pos:?} in\n{track}");
+            }
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use crate::parse::{Input, ParseError};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// A graph over string-labelled nodes, interned into dense ids in order of first appearance.
+/// Adjacency lists are kept sorted and free of duplicates.
+#[derive(Clone, Debug, Default)]
+pub struct Graph {
+    directed: bool,
+    labels: Vec<String>,
+    ids: HashMap<String, usize>,
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+fn insert_sorted(list: &mut Vec<usize>, id: usize) -> bool {
+    match list.binary_search(&id) {
+        Ok(_) => false,
+        Err(i) => {
+            list.insert(i, id);
+            true
+        }
+    }
+}
+
+impl Graph {
+    pub fn undirected() -> Self {
+        Self::default()
+    }
+
+    pub fn directed() -> Self {
+        Self {
+            directed: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn from_edges<'a>(
+        edges: impl IntoIterator<Item = (&'a str, &'a str)>,
+        directed: bool,
+    ) -> Self {
+        let mut graph = if directed {
+            Self::directed()
+        } else {
+            Self::undirected()
+        };
+
+        for (from, to) in edges {
+            graph.add_edge(from, to);
+        }
+
+        graph
+    }
+
+    /// Builds a graph from lines such as `a-b`, failing at the first line that lacks the separator
+    /// or leaves either end unnamed.
+    pub fn parse_edges(input: &str, separator: char, directed: bool) -> Result<Self, ParseError> {
+        let input = Input::new(input);
+
+        let edges = input.parse_lines(input.as_str(), |line| {
+            let (from, to) = input.split_once(line, &separator.to_string())?;
+
+            if from.is_empty() || to.is_empty() {
+                return Err(input.error(line, "a node on each end"));
+            }
+
+            Ok((from, to))
+        })?;
+
+        Ok(Self::from_edges(edges, directed))
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn nodes(&self) -> Range<usize> {
+        0..self.len()
+    }
+
+    /// Returns the id for `label`, interning it if it is new.
+    pub fn add_node(&mut self, label: &str) -> usize {
+        if let Some(&id) = self.ids.get(label) {
+            return id;
+        }
+
+        let id = self.labels.len();
+        self.labels.push(label.to_owned());
+        self.ids.insert(label.to_owned(), id);
+        self.successors.push(vec![]);
+        self.predecessors.push(vec![]);
+
+        id
+    }
+
+    pub fn add_edge(&mut self, from: &str, to: &str) -> (usize, usize) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+
+        self.add_edge_by_id(from, to);
+
+        (from, to)
+    }
+
+    pub fn add_edge_by_id(&mut self, from: usize, to: usize) {
+        insert_sorted(&mut self.successors[from], to);
+        insert_sorted(&mut self.predecessors[to], from);
+
+        if !self.directed {
+            insert_sorted(&mut self.successors[to], from);
+            insert_sorted(&mut self.predecessors[from], to);
+        }
+    }
+
+    pub fn id(&self, label: &str) -> Option<usize> {
+        self.ids.get(label).copied()
+    }
+
+    pub fn label(&self, id: usize) -> &str {
+        &self.labels[id]
+    }
+
+    /// Nodes reachable from `id` over one edge, in ascending id order. For undirected graphs these
+    /// are all the neighbours.
+    pub fn neighbours(&self, id: usize) -> &[usize] {
+        &self.successors[id]
+    }
+
+    /// Nodes with an edge into `id`, in ascending id order.
+    pub fn predecessors(&self, id: usize) -> &[usize] {
+        &self.predecessors[id]
+    }
+
+    pub fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.successors[from].binary_search(&to).is_ok()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        let count = self.successors.iter().map(Vec::len).sum::<usize>();
+
+        if self.directed {
+            return count;
+        }
+
+        // An undirected edge is in the lists of both its ends, unless it is a loop.
+        let loops = self.nodes().filter(|&id| self.has_edge(id, id)).count();
+
+        (count + loops) / 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undirected() {
+        let graph = Graph::parse_edges("kh-tc\nqp-kh\nde-cg\nkh-tc\n", '-', false).unwrap();
+
+        assert_eq!(5, graph.len());
+        assert_eq!(3, graph.edge_count());
+
+        let kh = graph.id("kh").unwrap();
+        let tc = graph.id("tc").unwrap();
+        let qp = graph.id("qp").unwrap();
+
+        assert_eq!("kh", graph.label(kh));
+        assert_eq!([tc, qp], graph.neighbours(kh));
+        assert_eq!(graph.neighbours(kh), graph.predecessors(kh));
+        assert!(graph.has_edge(tc, kh));
+        assert!(!graph.has_edge(tc, qp));
+        assert_eq!(None, graph.id("zz"));
+    }
+
+    #[test]
+    fn test_loops() {
+        let undirected = Graph::parse_edges("a-a\na-b\n", '-', false).unwrap();
+        let directed = Graph::parse_edges("a-a\na-b\n", '-', true).unwrap();
+
+        assert_eq!(2, undirected.edge_count());
+        assert_eq!(2, directed.edge_count());
+        assert!(undirected.has_edge(0, 0));
+    }
+
+    #[test]
+    fn test_directed() {
+        let mut graph = Graph::directed();
+        let (x, z) = graph.add_edge("x00", "z00");
+        let (y, _) = graph.add_edge("y00", "z00");
+
+        assert_eq!(2, graph.edge_count());
+        assert_eq!([z], graph.neighbours(x));
+        assert!(graph.neighbours(z).is_empty());
+        assert_eq!([x, y], graph.predecessors(z));
+        assert!(graph.has_edge(x, z));
+        assert!(!graph.has_edge(z, x));
+    }
+
+    #[test]
+    fn test_parse_rejects_missing_separator() {
+        let err = Graph::parse_edges("a-b\nab\n", '-', false).unwrap_err();
+        assert_eq!((2, "ab"), (err.line, err.text.as_str()));
+
+        assert!(Graph::parse_edges("a-\n", '-', false).is_err());
+    }
+}
@@ -0,0 +1,229 @@
+use crate::parse::{Input, ParseError};
+use crate::point::Point;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// A dense `rows` by `cols` grid, stored row-major and indexed by [`Point`].
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Grid<T> {
+    rows: usize,
+    cols: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(rows: usize, cols: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            rows,
+            cols,
+            cells: vec![fill; rows * cols],
+        }
+    }
+
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(Point) -> T) -> Self {
+        Self {
+            rows,
+            cols,
+            cells: (0..rows * cols)
+                .map(|index| f(Point::from_index(index, cols)))
+                .collect(),
+        }
+    }
+
+    pub const fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub const fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub const fn in_bounds(&self, point: Point) -> bool {
+        point.in_bounds(self.rows, self.cols)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        point
+            .to_index(self.rows, self.cols)
+            .map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        point
+            .to_index(self.rows, self.cols)
+            .map(|index| &mut self.cells[index])
+    }
+
+    /// Swaps the cells at `a` and `b`. Panics if either is out of bounds.
+    pub fn swap(&mut self, a: Point, b: Point) {
+        let index = |point: Point| {
+            point
+                .to_index(self.rows, self.cols)
+                .unwrap_or_else(|| panic!("{point:?} out of bounds for Grid"))
+        };
+
+        let (a, b) = (index(a), index(b));
+        self.cells.swap(a, b);
+    }
+
+    /// Every point in row-major order.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let cols = self.cols;
+
+        (0..self.cells.len()).map(move |index| Point::from_index(index, cols))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    /// The first point in row-major order whose cell satisfies `predicate`.
+    pub fn position(&self, predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .map(|index| Point::from_index(index, self.cols))
+    }
+
+    /// As [`Grid::parse`], converting each character with `cell` and failing at the first one it
+    /// rejects, which should have been `expected`.
+    pub fn parse_with(
+        input: &str,
+        expected: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let shape = Grid::parse(input)?;
+
+        let cells = input
+            .lines()
+            .filter(|line| !line.is_empty())
+            .flat_map(|line| line.char_indices().map(move |(i, c)| (&line[i..], c)))
+            .map(|(text, c)| {
+                cell(c).ok_or_else(|| Input::new(input).error(&text[..c.len_utf8()], expected))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            rows: shape.rows,
+            cols: shape.cols,
+            cells,
+        })
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(Point, &T) -> U) -> Grid<U> {
+        Grid {
+            rows: self.rows,
+            cols: self.cols,
+            cells: self.iter().map(|(point, cell)| f(point, cell)).collect(),
+        }
+    }
+}
+
+impl Grid<char> {
+    /// One row per non-empty line, failing at the first line whose length differs from the first.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut cols = None;
+        let mut cells = vec![];
+
+        for line in input.lines().filter(|line| !line.is_empty()) {
+            let before = cells.len();
+            cells.extend(line.chars());
+
+            let expected = *cols.get_or_insert(cells.len() - before);
+
+            if expected != cells.len() - before {
+                return Err(Input::new(input).error(line, format!("{expected} columns")));
+            }
+        }
+
+        let cols = cols.unwrap_or(0);
+
+        Ok(Self {
+            rows: cells.len().checked_div(cols).unwrap_or(0),
+            cols,
+            cells,
+        })
+    }
+}
+
+/// One line per row, the inverse of [`Grid::parse`].
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.cells.chunks(self.cols.max(1)) {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{point:?} out of bounds for Grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{point:?} out of bounds for Grid"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse("#.S\n.#E\n").unwrap();
+
+        assert_eq!((2, 3), (grid.rows(), grid.cols()));
+        assert_eq!('E', grid[Point::new(1, 2)]);
+        assert_eq!(None, grid.get(Point::new(2, 0)));
+        assert_eq!(Some(Point::new(0, 2)), grid.position(|&c| c == 'S'));
+        assert_eq!("#.S\n.#E\n", grid.to_string());
+
+        let mut grid = grid;
+        grid.swap(Point::new(0, 2), Point::new(1, 0));
+        assert_eq!("#..\nS#E\n", grid.to_string());
+
+        let err = Grid::parse("##\n#\n").unwrap_err();
+        assert_eq!((2, "2 columns"), (err.line, err.expected.as_str()));
+    }
+
+    #[test]
+    fn test_parse_with() {
+        let grid = Grid::parse_with("#.\n.#\n", "'#' or '.'", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap();
+
+        assert_eq!(Some(&true), grid.get(Point::new(1, 1)));
+
+        let err =
+            Grid::parse_with("#.\n.x\n", "'#' or '.'", |c| (c != 'x').then_some(c)).unwrap_err();
+        assert_eq!((2, 2, "x"), (err.line, err.column, err.text.as_str()));
+    }
+
+    #[test]
+    fn test_map() {
+        let mut grid = Grid::from_fn(2, 2, |p| p.x * 2 + p.y);
+        grid[Point::new(1, 1)] = 9;
+
+        let doubled = grid.map(|_, &n| n * 2);
+
+        assert_eq!(
+            vec![0, 2, 4, 18],
+            doubled.iter().map(|(_, &n)| n).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use crate::generate;
+use std::env;
+use std::path::PathBuf;
+
+/// Where `get_input` saves a file for `day`, e.g. `src/bin/day06/input.txt`.
+pub fn path(day: &str, file: &str) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "src", "bin", day, file]
+        .iter()
+        .collect()
+}
+
+/// The named input file for `day`, or `None` if it has not been fetched.
+pub fn try_read_file(day: &str, file: &str) -> Option<String> {
+    std::fs::read_to_string(path(day, file)).ok()
+}
+
+/// Panics with a hint on how to fetch the file if it is missing.
+pub fn read_file(day: &str, file: &str) -> String {
+    try_read_file(day, file).unwrap_or_else(|| {
+        panic!(
+            "{} is missing, fetch it with `cargo run --bin get_input <day>`",
+            path(day, file).display()
+        )
+    })
+}
+
+/// The puzzle input for `day`, from its `input.txt`.
+pub fn read(day: &str) -> String {
+    read_file(day, "input.txt")
+}
+
+pub fn try_read(day: &str) -> Option<String> {
+    try_read_file(day, "input.txt")
+}
+
+/// The input benches run against: a generated one of size `$AOC_SIZE`, seeded by `$AOC_SEED`, if
+/// that is set, or the puzzle input otherwise. A size sweep is then
+/// `for size in 100 1000 10000; do AOC_SIZE=$size cargo bench --bin day06; done`.
+pub fn bench(day: &str) -> Option<String> {
+    let Ok(size) = env::var("AOC_SIZE") else {
+        return try_read(day);
+    };
+
+    let size = size.parse().expect("AOC_SIZE must be a number");
+    let seed =
+        env::var("AOC_SEED").map_or(0, |seed| seed.parse().expect("AOC_SEED must be a number"));
+
+    generate::generate(day, size, seed)
+}
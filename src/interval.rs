@@ -0,0 +1,321 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// A set of `usize` positions stored as disjoint, non-adjacent half-open runs. Inserting merges
+/// touching runs and removing splits them, so each run is always maximal.
+#[derive(Clone, Debug, Default)]
+pub struct IntervalSet {
+    runs: BTreeMap<usize, usize>,
+    lengths: Lengths,
+}
+
+// A segment tree over positions holding the length of the run starting at each, so the leftmost
+// run of at least some length is found by going left wherever the longest run there is enough.
+// Nodes are only made for the parts of the range that have held a run.
+#[derive(Clone, Debug, Default)]
+struct Lengths {
+    // Node 0 stands in for missing children, holding no run, and node 1 is the root.
+    nodes: Vec<Node>,
+    // The tree covers positions `0..1 << height`.
+    height: u32,
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+struct Node {
+    longest: usize,
+    children: [usize; 2],
+}
+
+impl Lengths {
+    fn set(&mut self, pos: usize, length: usize) {
+        if self.nodes.is_empty() {
+            self.nodes = vec![Node::default(); 2];
+        }
+
+        // Grow upwards until `pos` is covered, with the old tree as the new root's left half.
+        while self.height < usize::BITS && pos >> self.height != 0 {
+            let root = self.nodes[1];
+            self.nodes.push(root);
+            self.nodes[1].children = [self.nodes.len() - 1, 0];
+            self.height += 1;
+        }
+
+        // The nodes from the root down to `pos`, with one level per bit.
+        let mut path = [1; usize::BITS as usize + 1];
+        let depth = self.height as usize;
+
+        for (level, i) in (0..self.height).rev().zip(0..) {
+            let node = path[i];
+            let side = pos >> level & 1;
+
+            if self.nodes[node].children[side] == 0 {
+                self.nodes.push(Node::default());
+                self.nodes[node].children[side] = self.nodes.len() - 1;
+            }
+
+            path[i + 1] = self.nodes[node].children[side];
+        }
+
+        self.nodes[path[depth]].longest = length;
+
+        for &node in path[..depth].iter().rev() {
+            let [left, right] = self.nodes[node].children;
+            self.nodes[node].longest = self.nodes[left].longest.max(self.nodes[right].longest);
+        }
+    }
+
+    // The leftmost position holding at least `length`, which must not be zero.
+    fn first(&self, length: usize) -> Option<usize> {
+        if self.nodes.get(1)?.longest < length {
+            return None;
+        }
+
+        let mut node = 1;
+        let mut pos = 0;
+
+        for level in (0..self.height).rev() {
+            let [left, right] = self.nodes[node].children;
+
+            if self.nodes[left].longest >= length {
+                node = left;
+            } else {
+                node = right;
+                pos |= 1 << level;
+            }
+        }
+
+        Some(pos)
+    }
+}
+
+// Sets are equal by their runs, however their trees happen to be laid out.
+impl PartialEq for IntervalSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.runs == other.runs
+    }
+}
+
+impl Eq for IntervalSet {}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn add_run(&mut self, start: usize, end: usize) {
+        if start < end {
+            self.runs.insert(start, end);
+            self.lengths.set(start, end - start);
+        }
+    }
+
+    fn remove_run(&mut self, start: usize) -> usize {
+        let end = self.runs.remove(&start).unwrap();
+        self.lengths.set(start, 0);
+        end
+    }
+
+    /// The run starting before `pos` that extends past it, if any.
+    fn run_across(&self, pos: usize) -> Option<(usize, usize)> {
+        self.runs
+            .range(..pos)
+            .next_back()
+            .filter(|&(_, &end)| end > pos)
+            .map(|(&start, &end)| (start, end))
+    }
+
+    /// Number of runs.
+    pub fn len(&self) -> usize {
+        self.runs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    pub fn insert(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let mut start = range.start;
+        let mut end = range.end;
+
+        if let Some((&s, &e)) = self.runs.range(..start).next_back() {
+            if e >= start {
+                start = s;
+                end = end.max(self.remove_run(s));
+            }
+        }
+
+        while let Some((&s, _)) = self.runs.range(start..=end).next() {
+            end = end.max(self.remove_run(s));
+        }
+
+        self.add_run(start, end);
+    }
+
+    pub fn remove(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+
+        if let Some((s, e)) = self.run_across(range.start) {
+            self.remove_run(s);
+            self.add_run(s, range.start);
+            self.add_run(range.end, e);
+        }
+
+        while let Some((&s, _)) = self.runs.range(range.clone()).next() {
+            let e = self.remove_run(s);
+            self.add_run(range.end, e);
+        }
+    }
+
+    pub fn contains(&self, pos: usize) -> bool {
+        self.run_containing(pos).is_some()
+    }
+
+    pub fn run_containing(&self, pos: usize) -> Option<Range<usize>> {
+        self.runs
+            .range(..=pos)
+            .next_back()
+            .filter(|&(_, &end)| end > pos)
+            .map(|(&start, &end)| start..end)
+    }
+
+    /// Whole runs sharing at least one position with `range`, in ascending order.
+    pub fn overlapping(&self, range: Range<usize>) -> impl Iterator<Item = Range<usize>> + '_ {
+        // An empty range overlaps nothing, and a reversed one would make `BTreeMap::range` panic.
+        let (across, range) = if range.is_empty() {
+            (None, 0..0)
+        } else {
+            (self.run_across(range.start), range)
+        };
+
+        across
+            .map(|(start, end)| start..end)
+            .into_iter()
+            .chain(self.runs.range(range).map(|(&start, &end)| start..end))
+    }
+
+    /// The leftmost `start` such that `start..start + length` is in the set and ends by `limit`.
+    /// Takes time logarithmic in the largest position the set has held.
+    pub fn first_fit(&self, length: usize, limit: usize) -> Option<usize> {
+        // Any run that fits starts at or after the leftmost long enough one, so ends later.
+        self.lengths
+            .first(length.max(1))
+            .filter(|&start| start + length <= limit)
+    }
+
+    pub fn first(&self) -> Option<Range<usize>> {
+        self.runs.first_key_value().map(|(&start, &end)| start..end)
+    }
+
+    /// Runs in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.runs.iter().map(|(&start, &end)| start..end)
+    }
+}
+
+impl FromIterator<Range<usize>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Range<usize>>>(iter: I) -> Self {
+        let mut set = Self::new();
+
+        for range in iter {
+            set.insert(range);
+        }
+
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_insert_merges() {
+        let mut set = IntervalSet::from_iter([0..2, 5..7, 9..10]);
+
+        assert_eq!(vec![0..2, 5..7, 9..10], set.iter().collect::<Vec<_>>());
+
+        set.insert(2..3);
+        set.insert(6..9);
+
+        assert_eq!(vec![0..3, 5..10], set.iter().collect::<Vec<_>>());
+        assert!(set.contains(9));
+        assert!(!set.contains(3));
+        assert_eq!(Some(5..10), set.run_containing(7));
+    }
+
+    #[test]
+    fn test_remove_splits() {
+        let mut set = IntervalSet::from_iter([0..10, 12..15, 20..30]);
+
+        set.remove(3..5);
+        set.remove(8..22);
+
+        assert_eq!(vec![0..3, 5..8, 22..30], set.iter().collect::<Vec<_>>());
+        assert_eq!(vec![0..3, 5..8], set.overlapping(2..6).collect::<Vec<_>>());
+        assert_eq!(0, set.overlapping(8..22).count());
+        assert_eq!(0, set.overlapping(6..6).count());
+        assert_eq!(0, set.overlapping(Range { start: 25, end: 3 }).count());
+    }
+
+    #[test]
+    fn test_first_fit() {
+        let set = IntervalSet::from_iter([2..3, 5..9, 12..14, 20..26]);
+
+        assert_eq!(Some(2), set.first_fit(1, 100));
+        assert_eq!(Some(5), set.first_fit(2, 100));
+        assert_eq!(Some(20), set.first_fit(5, 100));
+        assert_eq!(None, set.first_fit(5, 24));
+        assert_eq!(Some(5), set.first_fit(2, 7));
+        assert_eq!(None, set.first_fit(2, 6));
+        assert_eq!(None, set.first_fit(7, 100));
+    }
+
+    #[test]
+    fn test_first_fit_far_out() {
+        let mut set =
+            IntervalSet::from_iter([3..4, 1 << 40..(1 << 40) + 8, usize::MAX - 2..usize::MAX]);
+
+        assert_eq!(Some(1 << 40), set.first_fit(2, usize::MAX));
+        assert_eq!(None, set.first_fit(9, usize::MAX));
+
+        set.remove(1 << 40..(1 << 40) + 8);
+
+        assert_eq!(Some(usize::MAX - 2), set.first_fit(2, usize::MAX));
+        assert_eq!(Some(3), set.first_fit(1, usize::MAX));
+    }
+
+    // Checks `first_fit` against a scan of the runs as they are inserted and removed at random.
+    #[test]
+    fn test_first_fit_matches_scan() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut set = IntervalSet::new();
+
+        for _ in 0..2000 {
+            let start = rng.gen_range(0..200);
+            let range = start..start + rng.gen_range(0..10);
+
+            if rng.gen_bool(0.5) {
+                set.insert(range);
+            } else {
+                set.remove(range);
+            }
+
+            let (length, limit) = (rng.gen_range(0..8), rng.gen_range(0..220));
+            let expected = set
+                .iter()
+                .find(|run| run.len() >= length.max(1))
+                .map(|run| run.start)
+                .filter(|&start| start + length <= limit);
+
+            assert_eq!(expected, set.first_fit(length, limit));
+        }
+    }
+}
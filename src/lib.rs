@@ -0,0 +1,18 @@
+pub mod bitset;
+pub mod cycle;
+pub mod fuzz;
+pub mod generate;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod interval;
+pub mod linalg;
+pub mod memo;
+pub mod number;
+pub mod parse;
+pub mod point;
+pub mod render;
+pub mod search;
+pub mod snapshot;
+pub mod trie;
+pub mod union_find;
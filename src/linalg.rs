@@ -0,0 +1,244 @@
+use crate::number;
+use num::rational::Ratio;
+use num::{Integer, Signed, Zero};
+
+/// The outcome of solving `a · x = b` exactly.
+#[derive(Clone, Debug)]
+pub enum Solution<T> {
+    Unique(Vec<Ratio<T>>),
+    /// Infinitely many solutions. `particular` is the one with every `free` variable set to zero.
+    Underdetermined {
+        particular: Vec<Ratio<T>>,
+        free: Vec<usize>,
+    },
+    Inconsistent,
+}
+
+impl<T: Clone + Integer> PartialEq for Solution<T> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Unique(a), Self::Unique(b)) => a == b,
+            (
+                Self::Underdetermined {
+                    particular: a,
+                    free: a_free,
+                },
+                Self::Underdetermined {
+                    particular: b,
+                    free: b_free,
+                },
+            ) => a == b && a_free == b_free,
+            (Self::Inconsistent, Self::Inconsistent) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: Clone + Integer> Eq for Solution<T> {}
+
+impl<T: Clone + Integer> Solution<T> {
+    /// The unique solution, if there is one and all of it is integral.
+    pub fn integer(&self) -> Option<Vec<T>> {
+        match self {
+            Self::Unique(x) => x
+                .iter()
+                .map(|v| v.is_integer().then(|| v.to_integer()))
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
+/// Gauss-Jordan elimination over the rationals. `a` may have any number of rows and columns, with
+/// one entry of `b` per row. Entries grow as rows are combined, so a fixed-width `T` can overflow
+/// on large inputs: use `BigInt` where they are not bounded.
+pub fn solve<T: Clone + Integer + Signed>(a: &[Vec<T>], b: &[T]) -> Solution<T> {
+    assert_eq!(a.len(), b.len(), "one right-hand side per row");
+
+    let cols = a.first().map_or(0, Vec::len);
+
+    let mut rows = a
+        .iter()
+        .zip(b)
+        .map(|(row, rhs)| {
+            assert_eq!(cols, row.len(), "rows of differing length");
+
+            row.iter()
+                .chain([rhs])
+                .map(|v| Ratio::from_integer(v.clone()))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut pivots = vec![];
+
+    for col in 0..cols {
+        let rank = pivots.len();
+
+        let Some(pivot) = (rank..rows.len()).find(|&r| !rows[r][col].is_zero()) else {
+            continue;
+        };
+
+        rows.swap(rank, pivot);
+
+        let scale = rows[rank][col].clone();
+
+        for v in &mut rows[rank][col..] {
+            *v = &*v / &scale;
+        }
+
+        let pivot_row = rows[rank].clone();
+
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r == rank || row[col].is_zero() {
+                continue;
+            }
+
+            let factor = row[col].clone();
+
+            for (v, p) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *v = &*v - &factor * p;
+            }
+        }
+
+        pivots.push(col);
+    }
+
+    if rows[pivots.len()..].iter().any(|row| !row[cols].is_zero()) {
+        return Solution::Inconsistent;
+    }
+
+    let mut x = vec![Ratio::zero(); cols];
+
+    for (row, &col) in rows.iter().zip(&pivots) {
+        x[col] = row[cols].clone();
+    }
+
+    if pivots.len() == cols {
+        Solution::Unique(x)
+    } else {
+        Solution::Underdetermined {
+            particular: x,
+            free: (0..cols).filter(|c| !pivots.contains(c)).collect(),
+        }
+    }
+}
+
+/// Every integer solution of `a·x + b·y = c`, as `(x + k·dx, y - k·dy)` for integer `k`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Diophantine<T> {
+    pub x: T,
+    pub y: T,
+    pub dx: T,
+    pub dy: T,
+}
+
+impl<T: Clone + Integer + Signed> Diophantine<T> {
+    pub fn at(&self, k: T) -> (T, T) {
+        (
+            self.x.clone() + k.clone() * self.dx.clone(),
+            self.y.clone() - k * self.dy.clone(),
+        )
+    }
+
+    /// The inclusive range of `k` for which both `x` and `y` are non-negative, if any. Assumes `a`
+    /// and `b` were positive.
+    pub fn non_negative(&self) -> Option<(T, T)> {
+        let low = -self.x.div_floor(&self.dx);
+        let high = self.y.div_floor(&self.dy);
+
+        (low <= high).then_some((low, high))
+    }
+}
+
+/// Solves `a·x + b·y = c` with the extended Euclidean algorithm, or returns `None` if it has no
+/// integer solutions. `a` and `b` must not both be zero.
+pub fn diophantine<T: Clone + Integer + Signed>(a: T, b: T, c: T) -> Option<Diophantine<T>> {
+    assert!(!(a.is_zero() && b.is_zero()), "a and b are both zero");
+
+    let (gcd, x, y) = number::extended_gcd(a.clone(), b.clone());
+
+    if !c.is_multiple_of(&gcd) {
+        return None;
+    }
+
+    let k = c / gcd.clone();
+
+    Some(Diophantine {
+        x: x * k.clone(),
+        y: y * k,
+        dx: b / gcd.clone(),
+        dy: a / gcd,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::BigInt;
+
+    #[test]
+    fn test_unique() {
+        let a = [vec![2, 1, -1], vec![-3, -1, 2], vec![-2, 1, 2]];
+        let solution = solve(&a, &[8, -11, -3]);
+
+        assert_eq!(Some(vec![2, 3, -1]), solution.integer());
+
+        let solution = solve(&[vec![94, 22], vec![34, 67]], &[8400, 5400]);
+        assert_eq!(Some(vec![80, 40]), solution.integer());
+
+        let solution = solve(&[vec![94, 22], vec![34, 67]], &[8401, 5400]);
+        assert_eq!(
+            Solution::Unique(vec![Ratio::new(444_067, 5550), Ratio::new(110_983, 2775)]),
+            solution
+        );
+        assert_eq!(None, solution.integer());
+    }
+
+    #[test]
+    fn test_big() {
+        let a = [[3000000007i64, 2000000011], [2999999999, 3000000019]]
+            .map(|row| row.map(BigInt::from).to_vec());
+        let b = [12000000047000i64, 15000000055000].map(BigInt::from);
+
+        assert_eq!(
+            Some(vec![BigInt::from(2000), BigInt::from(3000)]),
+            solve(&a, &b).integer()
+        );
+    }
+
+    #[test]
+    fn test_singular() {
+        let a = [vec![1, 2], vec![2, 4]];
+
+        assert_eq!(Solution::Inconsistent, solve(&a, &[3, 7]));
+        assert_eq!(
+            Solution::Underdetermined {
+                particular: vec![Ratio::from_integer(3), Ratio::zero()],
+                free: vec![1],
+            },
+            solve(&a, &[3, 6])
+        );
+        assert_eq!(
+            Solution::Underdetermined {
+                particular: vec![Ratio::from_integer(1), Ratio::zero(), Ratio::zero()],
+                free: vec![1, 2],
+            },
+            solve(&[vec![1, 1, 1]], &[1])
+        );
+    }
+
+    #[test]
+    fn test_diophantine() {
+        assert_eq!(None, diophantine(6, 4, 7));
+
+        let solutions = diophantine(6, 4, 50).unwrap();
+        let (low, high) = solutions.non_negative().unwrap();
+
+        assert_eq!(
+            vec![(1, 11), (3, 8), (5, 5), (7, 2)],
+            (low..=high).map(|k| solutions.at(k)).collect::<Vec<_>>()
+        );
+        assert_eq!(None, diophantine(6, 4, 2).unwrap().non_negative());
+    }
+}
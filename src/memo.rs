@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Storage for memoised results.
+pub trait Cache<K, V> {
+    fn get(&self, key: &K) -> Option<V>;
+
+    fn insert(&mut self, key: K, value: V);
+}
+
+impl<K: Eq + Hash, V: Clone> Cache<K, V> for HashMap<K, V> {
+    fn get(&self, key: &K) -> Option<V> {
+        HashMap::get(self, key).cloned()
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        HashMap::insert(self, key, value);
+    }
+}
+
+/// A cache for keys that map onto `0..len`, backed by a `Vec`.
+#[derive(Clone, Debug)]
+pub struct DenseCache<K, V> {
+    index: fn(&K) -> usize,
+    values: Vec<Option<V>>,
+}
+
+impl<K, V: Clone> DenseCache<K, V> {
+    pub fn new(len: usize, index: fn(&K) -> usize) -> Self {
+        Self {
+            index,
+            values: vec![None; len],
+        }
+    }
+}
+
+impl<K, V: Clone> Cache<K, V> for DenseCache<K, V> {
+    fn get(&self, key: &K) -> Option<V> {
+        self.values[(self.index)(key)].clone()
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        self.values[(self.index)(&key)] = Some(value);
+    }
+}
+
+type RecursiveFn<'a, K, V> = dyn Fn(&mut dyn FnMut(K) -> V, K) -> V + 'a;
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Stats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+/// A memoised recursive function. `f` receives a callback for recursing through the cache
+/// alongside the key to compute.
+pub struct Memo<K, V, F, C = HashMap<K, V>> {
+    f: F,
+    cache: C,
+    stats: Stats,
+    _marker: std::marker::PhantomData<fn(K) -> V>,
+}
+
+impl<K, V, F> Memo<K, V, F>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    pub fn new(f: F) -> Self {
+        Self::with_cache(HashMap::new(), f)
+    }
+}
+
+impl<K, V, F, C> Memo<K, V, F, C>
+where
+    K: Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+    C: Cache<K, V>,
+{
+    pub fn with_cache(cache: C, f: F) -> Self {
+        Self {
+            f,
+            cache,
+            stats: Stats::default(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    pub fn get(&mut self, key: K) -> V {
+        fn recurse<K: Clone, V: Clone, C: Cache<K, V>>(
+            f: &RecursiveFn<'_, K, V>,
+            cache: &mut C,
+            stats: &mut Stats,
+            key: K,
+        ) -> V {
+            if let Some(value) = cache.get(&key) {
+                stats.hits += 1;
+                return value;
+            }
+
+            stats.misses += 1;
+
+            let value = f(&mut |k| recurse(f, cache, stats, k), key.clone());
+            cache.insert(key, value.clone());
+
+            value
+        }
+
+        recurse(&self.f, &mut self.cache, &mut self.stats, key)
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fibonacci() {
+        let mut fib = Memo::new(|fib, n: u64| if n < 2 { n } else { fib(n - 1) + fib(n - 2) });
+
+        assert_eq!(12586269025, fib.get(50));
+        assert_eq!(
+            Stats {
+                hits: 48,
+                misses: 51
+            },
+            fib.stats()
+        );
+
+        assert_eq!(55, fib.get(10));
+        assert_eq!(49, fib.stats().hits);
+    }
+
+    #[test]
+    fn test_dense_cache() {
+        // Lattice paths through a 10x10 grid.
+        let mut paths = Memo::with_cache(
+            DenseCache::new(11 * 11, |&(x, y): &(usize, usize)| x * 11 + y),
+            |paths, (x, y)| {
+                if x == 0 || y == 0 {
+                    1u64
+                } else {
+                    paths((x - 1, y)) + paths((x, y - 1))
+                }
+            },
+        );
+
+        assert_eq!(184756, paths.get((10, 10)));
+        assert_eq!(121 - 1, paths.stats().misses);
+    }
+}
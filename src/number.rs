@@ -0,0 +1,122 @@
+use num::{Integer, Signed};
+
+pub use num::integer::{gcd, lcm};
+
+/// Number of digits of `n` in `base`, counting zero as one digit.
+pub fn digit_count(n: u64, base: u64) -> u32 {
+    n.checked_ilog(base).map_or(1, |log| log + 1)
+}
+
+/// Splits off the lowest `at` digits of `n`, returning `(high, low)`.
+pub fn split_digits(n: u64, at: u32, base: u64) -> (u64, u64) {
+    match base.checked_pow(at) {
+        Some(power) => n.div_rem(&power),
+        None => (0, n),
+    }
+}
+
+/// The digits of `a` followed by the digits of `b`, or `None` on overflow.
+pub fn checked_concat(a: u64, b: u64, base: u64) -> Option<u64> {
+    base.checked_pow(digit_count(b, base))?
+        .checked_mul(a)?
+        .checked_add(b)
+}
+
+/// Panics on overflow, as with [`checked_concat`].
+pub fn concat(a: u64, b: u64, base: u64) -> u64 {
+    checked_concat(a, b, base).expect("concatenation overflowed")
+}
+
+/// The inverse of [`concat`]: strips the digits of `b` from the end of `n`, if it ends with them.
+pub fn strip_digits(n: u64, b: u64, base: u64) -> Option<u64> {
+    let (high, low) = split_digits(n, digit_count(b, base), base);
+
+    (low == b).then_some(high)
+}
+
+/// Returns `(g, x, y)` with `g = gcd(a, b) = a·x + b·y` and `g` non-negative.
+pub fn extended_gcd<T: Clone + Integer + Signed>(a: T, b: T) -> (T, T, T) {
+    let gcd = a.extended_gcd(&b);
+
+    (gcd.gcd, gcd.x, gcd.y)
+}
+
+/// The `x` in `0..m` with `a·x ≡ 1 (mod m)`, if `a` and `m` are coprime.
+pub fn mod_inverse<T: Clone + Integer + Signed>(a: T, m: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(a, m.clone());
+
+    g.is_one().then(|| x.mod_floor(&m))
+}
+
+/// Chinese remainder theorem over `(residue, modulus)` pairs, which need not be coprime. Returns
+/// the smallest non-negative solution along with the combined modulus, or `None` if the
+/// congruences conflict.
+pub fn crt<T: Clone + Integer + Signed>(
+    congruences: impl IntoIterator<Item = (T, T)>,
+) -> Option<(T, T)> {
+    let mut x = T::zero();
+    let mut m = T::one();
+
+    for (r, n) in congruences {
+        let (g, p, _) = extended_gcd(m.clone(), n.clone());
+        let diff = r - x.clone();
+
+        if !diff.is_multiple_of(&g) {
+            return None;
+        }
+
+        // x + m·t ≡ r (mod n), so t ≡ (r - x) / g · p (mod n / g).
+        let step = n / g.clone();
+        let t = (diff / g * p).mod_floor(&step);
+
+        x = x + m.clone() * t;
+        m = m * step;
+        x = x.mod_floor(&m);
+    }
+
+    Some((x, m))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(1, digit_count(0, 10));
+        assert_eq!(4, digit_count(2024, 10));
+        assert_eq!(8, digit_count(0b1010_0101, 2));
+        assert_eq!(20, digit_count(u64::MAX, 10));
+
+        assert_eq!((20, 24), split_digits(2024, 2, 10));
+        assert_eq!((0, 2024), split_digits(2024, 30, 10));
+
+        assert_eq!(156, concat(15, 6, 10));
+        assert_eq!(0x1f0, concat(0x1f, 0, 16));
+        assert_eq!(None, checked_concat(u64::MAX / 10, 99, 10));
+
+        assert_eq!(Some(15), strip_digits(156, 6, 10));
+        assert_eq!(Some(1), strip_digits(156, 56, 10));
+        assert_eq!(None, strip_digits(156, 7, 10));
+    }
+
+    #[test]
+    fn test_number_theory() {
+        assert_eq!(6, gcd(48, 18));
+        assert_eq!(144, lcm(48, 18));
+
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!((2, 2), (g, 240 * x + 46 * y));
+
+        assert_eq!(Some(4), mod_inverse(3, 11));
+        assert_eq!(None, mod_inverse(4, 8));
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(Some((23, 105)), crt([(2, 3), (3, 5), (2, 7)]));
+        assert_eq!(Some((10, 12)), crt([(4, 6), (2, 4)]));
+        assert_eq!(None, crt([(1, 6), (2, 4)]));
+        assert_eq!(Some((0, 1)), crt::<i64>([]));
+    }
+}
@@ -0,0 +1,227 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Malformed input, located by 1-based line and column.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub expected: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found {:?}",
+            self.line, self.column, self.expected, self.text
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// A puzzle input. Every `&str` handed to its methods must be a slice of the input itself, which
+/// is how errors find their line and column.
+#[derive(Copy, Clone, Debug)]
+pub struct Input<'a>(&'a str);
+
+impl<'a> Input<'a> {
+    pub const fn new(input: &'a str) -> Self {
+        Self(input)
+    }
+
+    pub const fn as_str(self) -> &'a str {
+        self.0
+    }
+
+    pub fn error(self, text: &str, expected: impl Into<String>) -> ParseError {
+        let offset = (text.as_ptr() as usize)
+            .checked_sub(self.0.as_ptr() as usize)
+            .filter(|&offset| offset <= self.0.len())
+            .unwrap_or(0);
+
+        let before = &self.0[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            text: text.to_owned(),
+            expected: expected.into(),
+        }
+    }
+
+    pub fn parse<T: FromStr>(self, text: &str) -> Result<T, ParseError> {
+        text.parse()
+            .map_err(|_| self.error(text, std::any::type_name::<T>()))
+    }
+
+    pub fn split_once(
+        self,
+        text: &'a str,
+        delimiter: &str,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        text.split_once(delimiter)
+            .ok_or_else(|| self.error(text, format!("{delimiter:?}")))
+    }
+
+    pub fn strip_prefix(self, text: &'a str, prefix: &str) -> Result<&'a str, ParseError> {
+        text.strip_prefix(prefix)
+            .ok_or_else(|| self.error(text, format!("{prefix:?}")))
+    }
+
+    /// Every optionally negative run of digits in `text`, ignoring whatever separates them.
+    pub fn integers<T: FromStr>(self, text: &'a str) -> Result<Vec<T>, ParseError> {
+        let bytes = text.as_bytes();
+        let mut integers = vec![];
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let negative = bytes[i] == b'-' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+
+            if !negative && !bytes[i].is_ascii_digit() {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            i += 1;
+
+            while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+                i += 1;
+            }
+
+            integers.push(self.parse(&text[start..i])?);
+        }
+
+        Ok(integers)
+    }
+
+    /// Exactly `K` integers from `text`, as with [`Input::integers`].
+    pub fn integers_n<T: FromStr, const K: usize>(
+        self,
+        text: &'a str,
+    ) -> Result<[T; K], ParseError> {
+        self.integers(text)?
+            .try_into()
+            .map_err(|_| self.error(text, format!("{K} integers")))
+    }
+
+    /// Non-empty lines of `text`.
+    pub fn lines(self, text: &'a str) -> impl Iterator<Item = &'a str> {
+        text.lines().filter(|line| !line.trim().is_empty())
+    }
+
+    /// Parses every non-empty line of `text`, stopping at the first error.
+    pub fn parse_lines<T>(
+        self,
+        text: &'a str,
+        parse_line: impl FnMut(&'a str) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        self.lines(text).map(parse_line).collect()
+    }
+
+    /// Runs of non-blank lines, without their trailing newline.
+    pub fn sections(self) -> Vec<&'a str> {
+        let mut sections = vec![];
+        let mut start = None;
+        let mut offset = 0;
+
+        for line in self.0.split_inclusive('\n') {
+            if line.trim().is_empty() {
+                if let Some(s) = start.take() {
+                    sections.push(self.0[s..offset].trim_end());
+                }
+            } else if start.is_none() {
+                start = Some(offset);
+            }
+
+            offset += line.len();
+        }
+
+        if let Some(s) = start {
+            sections.push(self.0[s..].trim_end());
+        }
+
+        sections
+    }
+
+    /// Exactly `K` sections, as with [`Input::sections`].
+    pub fn sections_n<const K: usize>(self) -> Result<[&'a str; K], ParseError> {
+        self.sections()
+            .try_into()
+            .map_err(|_| self.error(self.0, format!("{K} blank-line separated sections")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_location() {
+        let text = "12 34\n56 7x\n";
+        let input = Input::new(text);
+
+        let err = input.parse::<u32>(&text[9..11]).unwrap_err();
+
+        assert_eq!((2, 4), (err.line, err.column));
+        assert_eq!("7x", err.text);
+        assert_eq!(
+            "line 2, column 4: expected u32, found \"7x\"",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_integers() {
+        let text = "p=0,4 v=3,-3 a-b 12-5";
+        let input = Input::new(text);
+
+        assert_eq!(
+            vec![0, 4, 3, -3, 12, -5],
+            input.integers::<i32>(text).unwrap()
+        );
+        assert_eq!(
+            [0, 4, 3, -3, 12, -5],
+            input.integers_n::<i32, 6>(text).unwrap()
+        );
+        assert!(input.integers_n::<i32, 5>(text).is_err());
+
+        let err = input.integers::<u32>(text).unwrap_err();
+        assert_eq!((1, 11), (err.line, err.column));
+    }
+
+    #[test]
+    fn test_sections() {
+        let text = "a\nb\n\n\nc\n  \nd\ne\n";
+        let input = Input::new(text);
+
+        assert_eq!(vec!["a\nb", "c", "d\ne"], input.sections());
+        assert!(input.sections_n::<2>().is_err());
+
+        let [_, c, _] = input.sections_n().unwrap();
+        assert_eq!(5, input.error(c, "").line);
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let text = "1-2\n\n3-4\n5+6\n";
+        let input = Input::new(text);
+
+        let parse_line = |line| {
+            let (lhs, rhs) = input.split_once(line, "-")?;
+            Ok((input.parse::<u8>(lhs)?, input.parse::<u8>(rhs)?))
+        };
+
+        let err = input.parse_lines(text, parse_line).unwrap_err();
+        assert_eq!((4, 1, "5+6"), (err.line, err.column, err.text.as_str()));
+        assert_eq!(
+            vec![(1, 2), (3, 4)],
+            input.parse_lines(&text[..9], parse_line).unwrap()
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A grid coordinate, `x` being the row and `y` the column.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point {
+    pub const ZERO: Self = Self::new(0, 0);
+
+    /// Offsets to the 8 surrounding cells, row by row.
+    pub const ADJACENT: [Self; 8] = [
+        Self::new(-1, -1),
+        Self::new(-1, 0),
+        Self::new(-1, 1),
+        Self::new(0, -1),
+        Self::new(0, 1),
+        Self::new(1, -1),
+        Self::new(1, 0),
+        Self::new(1, 1),
+    ];
+
+    pub const fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+
+    pub const fn from_index(index: usize, cols: usize) -> Self {
+        Self::new((index / cols) as isize, (index % cols) as isize)
+    }
+
+    pub const fn in_bounds(self, rows: usize, cols: usize) -> bool {
+        0 <= self.x && self.x < rows as isize && 0 <= self.y && self.y < cols as isize
+    }
+
+    /// Flat index into a row-major `rows * cols` grid, if the point lies inside it.
+    pub const fn to_index(self, rows: usize, cols: usize) -> Option<usize> {
+        if self.in_bounds(rows, cols) {
+            Some(self.x as usize * cols + self.y as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn step(self, dir: Direction) -> Self {
+        self + dir.offset()
+    }
+
+    pub fn checked_step(self, dir: Direction, rows: usize, cols: usize) -> Option<Self> {
+        let next = self.step(dir);
+
+        next.in_bounds(rows, cols).then_some(next)
+    }
+
+    pub fn neighbours(self) -> [Self; 4] {
+        Direction::ALL.map(|dir| self.step(dir))
+    }
+
+    pub const fn manhattan(self, other: Self) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Rotates the point a quarter turn clockwise around the origin.
+    pub const fn rotate_right(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+
+    /// Rotates the point a quarter turn anticlockwise around the origin.
+    pub const fn rotate_left(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Self;
+
+    fn mul(self, rhs: isize) -> Self {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Point {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl Add<Direction> for Point {
+    type Output = Self;
+
+    fn add(self, rhs: Direction) -> Self {
+        self.step(rhs)
+    }
+}
+
+impl AddAssign<Direction> for Point {
+    fn add_assign(&mut self, rhs: Direction) {
+        *self = self.step(rhs);
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All directions, clockwise from `Up`. `ALL[dir.index()] == dir`.
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    pub const fn from_char(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Self::Up),
+            '>' => Some(Self::Right),
+            'v' => Some(Self::Down),
+            '<' => Some(Self::Left),
+            _ => None,
+        }
+    }
+
+    pub const fn to_char(self) -> char {
+        match self {
+            Self::Up => '^',
+            Self::Right => '>',
+            Self::Down => 'v',
+            Self::Left => '<',
+        }
+    }
+
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    pub const fn offset(self) -> Point {
+        match self {
+            Self::Up => Point::new(-1, 0),
+            Self::Right => Point::new(0, 1),
+            Self::Down => Point::new(1, 0),
+            Self::Left => Point::new(0, -1),
+        }
+    }
+
+    pub const fn turn_right(self) -> Self {
+        Self::ALL[(self.index() + 1) % 4]
+    }
+
+    pub const fn turn_left(self) -> Self {
+        Self::ALL[(self.index() + 3) % 4]
+    }
+
+    pub const fn opposite(self) -> Self {
+        Self::ALL[(self.index() + 2) % 4]
+    }
+
+    pub const fn is_vertical(self) -> bool {
+        matches!(self, Self::Up | Self::Down)
+    }
+
+    /// Steps from a flat index in a row-major `rows * cols` grid, without wrapping around rows.
+    pub fn step_index(self, index: usize, rows: usize, cols: usize) -> Option<usize> {
+        Point::from_index(index, cols)
+            .step(self)
+            .to_index(rows, cols)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        for dir in Direction::ALL {
+            assert_eq!(dir, dir.turn_right().turn_left());
+            assert_eq!(dir.opposite(), dir.turn_right().turn_right());
+            assert_eq!(dir.turn_right().offset(), dir.offset().rotate_right());
+            assert_eq!(dir.turn_left().offset(), dir.offset().rotate_left());
+            assert_eq!(-dir.offset(), dir.opposite().offset());
+        }
+
+        assert_eq!(Direction::Right, Direction::Up.turn_right());
+    }
+
+    #[test]
+    fn test_index_round_trip() {
+        for index in 0..12 {
+            assert_eq!(Some(index), Point::from_index(index, 4).to_index(3, 4));
+        }
+
+        assert_eq!(Point::new(2, 1), Point::from_index(9, 4));
+        assert_eq!(None, Point::new(0, 4).to_index(3, 4));
+        assert_eq!(None, Point::new(-1, 0).to_index(3, 4));
+    }
+
+    #[test]
+    fn test_step_index_does_not_wrap() {
+        assert_eq!(None, Direction::Right.step_index(3, 3, 4));
+        assert_eq!(None, Direction::Left.step_index(4, 3, 4));
+        assert_eq!(None, Direction::Up.step_index(2, 3, 4));
+        assert_eq!(None, Direction::Down.step_index(9, 3, 4));
+        assert_eq!(Some(8), Direction::Down.step_index(4, 3, 4));
+        assert_eq!(Some(5), Direction::Right.step_index(4, 3, 4));
+    }
+
+    #[test]
+    fn test_manhattan() {
+        assert_eq!(7, Point::new(-2, 3).manhattan(Point::new(1, -1)));
+        assert_eq!(0, Point::ZERO.manhattan(Point::ZERO));
+    }
+}
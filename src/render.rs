@@ -0,0 +1,338 @@
+use crate::grid::Grid;
+use crate::point::Point;
+use std::fmt::Write as _;
+use std::io::{self, Write as _};
+use std::path::Path;
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Colour(pub u8, pub u8, pub u8);
+
+impl Colour {
+    pub const BLACK: Self = Self(0, 0, 0);
+    pub const DARK_GREY: Self = Self(48, 48, 48);
+    pub const GREY: Self = Self(128, 128, 128);
+    pub const WHITE: Self = Self(255, 255, 255);
+    pub const RED: Self = Self(220, 50, 47);
+    pub const GREEN: Self = Self(80, 200, 80);
+    pub const BLUE: Self = Self(38, 139, 210);
+    pub const YELLOW: Self = Self(240, 200, 40);
+}
+
+/// How a cell is drawn: `symbol` in text, `colour` in terminals and images.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Style {
+    pub symbol: char,
+    pub colour: Colour,
+}
+
+impl Style {
+    pub const fn new(symbol: char, colour: Colour) -> Self {
+        Self { symbol, colour }
+    }
+}
+
+/// A grid of styled cells, ready to be written out in any of the supported formats.
+#[derive(Clone, Debug)]
+pub struct Canvas {
+    cells: Grid<Style>,
+}
+
+impl Canvas {
+    pub fn new<T>(grid: &Grid<T>, style: impl FnMut(Point, &T) -> Style) -> Self {
+        Self {
+            cells: grid.map(style),
+        }
+    }
+
+    /// Restyles `points`, drawing over any earlier overlay. Points off the grid are skipped.
+    pub fn overlay(&mut self, points: impl IntoIterator<Item = Point>, style: Style) -> &mut Self {
+        for point in points {
+            if let Some(cell) = self.cells.get_mut(point) {
+                *cell = style;
+            }
+        }
+
+        self
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+
+        for (point, style) in self.cells.iter() {
+            text.push(style.symbol);
+
+            if point.y as usize == self.cells.cols() - 1 {
+                text.push('\n');
+            }
+        }
+
+        text
+    }
+
+    /// Text with 24-bit ANSI foreground colours, reset at the end of every line.
+    pub fn to_ansi(&self) -> String {
+        let mut text = String::new();
+        let mut current = None;
+
+        for (point, style) in self.cells.iter() {
+            if current != Some(style.colour) {
+                let Colour(r, g, b) = style.colour;
+                let _ = write!(text, "\x1b[38;2;{r};{g};{b}m");
+                current = Some(style.colour);
+            }
+
+            text.push(style.symbol);
+
+            if point.y as usize == self.cells.cols() - 1 {
+                text.push_str("\x1b[0m\n");
+                current = None;
+            }
+        }
+
+        text
+    }
+
+    /// Raw RGB rows, with every cell drawn as a `cell_size` pixel square.
+    fn pixels(&self, cell_size: usize) -> (usize, usize, Vec<u8>) {
+        let width = self.cells.cols() * cell_size;
+        let height = self.cells.rows() * cell_size;
+        let mut pixels = Vec::with_capacity(width * height * 3);
+
+        for y in 0..height {
+            for x in 0..width {
+                let point = Point::new((y / cell_size) as isize, (x / cell_size) as isize);
+                let Colour(r, g, b) = self.cells[point].colour;
+                pixels.extend([r, g, b]);
+            }
+        }
+
+        (width, height, pixels)
+    }
+
+    // Each row of `pixels`, which are empty for a canvas with no columns.
+    fn rows(pixels: &[u8], width: usize, height: usize) -> impl Iterator<Item = &[u8]> {
+        (0..height).map(move |y| &pixels[y * width * 3..(y + 1) * width * 3])
+    }
+
+    /// A binary PPM (P6) image.
+    pub fn to_ppm(&self, cell_size: usize) -> Vec<u8> {
+        let (width, height, pixels) = self.pixels(cell_size);
+        let mut image = format!("P6\n{width} {height}\n255\n").into_bytes();
+
+        image.extend(pixels);
+        image
+    }
+
+    /// A binary PBM (P4) image, inking every pixel that is not black.
+    pub fn to_pbm(&self, cell_size: usize) -> Vec<u8> {
+        let (width, height, pixels) = self.pixels(cell_size);
+        let mut image = format!("P4\n{width} {height}\n").into_bytes();
+
+        // Rows are packed eight pixels to a byte, most significant bit first, and padded to a byte.
+        for row in Self::rows(&pixels, width, height) {
+            for pixels in row.chunks(8 * 3) {
+                let byte = pixels
+                    .chunks(3)
+                    .enumerate()
+                    .filter(|(_, pixel)| *pixel != [0, 0, 0])
+                    .fold(0u8, |byte, (i, _)| byte | 0x80 >> i);
+
+                image.push(byte);
+            }
+        }
+
+        image
+    }
+
+    /// A truecolour PNG image. The pixel data is stored uncompressed.
+    pub fn to_png(&self, cell_size: usize) -> Vec<u8> {
+        let (width, height, pixels) = self.pixels(cell_size);
+
+        let mut ihdr = vec![];
+        ihdr.extend((width as u32).to_be_bytes());
+        ihdr.extend((height as u32).to_be_bytes());
+        // 8 bits per channel, RGB, default compression, filtering and no interlacing.
+        ihdr.extend([8, 2, 0, 0, 0]);
+
+        let mut scanlines = Vec::with_capacity(pixels.len() + height);
+
+        for row in Self::rows(&pixels, width, height) {
+            scanlines.push(0);
+            scanlines.extend(row);
+        }
+
+        let mut image = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut image, b"IHDR", &ihdr);
+        png_chunk(&mut image, b"IDAT", &zlib_stored(&scanlines));
+        png_chunk(&mut image, b"IEND", &[]);
+
+        image
+    }
+
+    /// Writes to `path` in the format its extension names (`png`, `ppm`, `pbm` or `ansi`), or as plain
+    /// text otherwise. A path of `-` prints ANSI text to stdout instead.
+    pub fn save(&self, path: impl AsRef<Path>, cell_size: usize) -> io::Result<()> {
+        let path = path.as_ref();
+
+        if path == Path::new("-") {
+            return io::stdout().write_all(self.to_ansi().as_bytes());
+        }
+
+        let contents = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("png") => self.to_png(cell_size),
+            Some("ppm") => self.to_ppm(cell_size),
+            Some("pbm") => self.to_pbm(cell_size),
+            Some("ansi") => self.to_ansi().into_bytes(),
+            _ => self.to_text().into_bytes(),
+        };
+
+        std::fs::write(path, contents)
+    }
+}
+
+fn png_chunk(image: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    image.extend((data.len() as u32).to_be_bytes());
+    image.extend(kind);
+    image.extend(data);
+    image.extend(crc32(kind.iter().chain(data)).to_be_bytes());
+}
+
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    let mut crc = !0u32;
+
+    for &byte in bytes {
+        crc ^= byte as u32;
+
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+// A zlib stream made of stored deflate blocks, which any decoder accepts without compression.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut stream = vec![0x78, 0x01];
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+
+    if blocks.peek().is_none() {
+        stream.extend([1, 0, 0, 0xff, 0xff]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+
+        stream.push(blocks.peek().is_none() as u8);
+        stream.extend(len.to_le_bytes());
+        stream.extend((!len).to_le_bytes());
+        stream.extend(block);
+    }
+
+    let (mut a, mut b) = (1u32, 0u32);
+
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    stream.extend(((b << 16) | a).to_be_bytes());
+    stream
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canvas() -> Canvas {
+        let grid = Grid::parse("#.\n.#\n").unwrap();
+
+        let mut canvas = Canvas::new(&grid, |_, &c| {
+            if c == '#' {
+                Style::new('#', Colour::GREY)
+            } else {
+                Style::new('.', Colour::BLACK)
+            }
+        });
+
+        canvas.overlay(
+            [Point::new(1, 0), Point::new(5, 5)],
+            Style::new('O', Colour::RED),
+        );
+        canvas
+    }
+
+    #[test]
+    fn test_text() {
+        let canvas = canvas();
+
+        assert_eq!("#.\nO#\n", canvas.to_text());
+        assert_eq!(
+            "\x1b[38;2;128;128;128m#\x1b[38;2;0;0;0m.\x1b[0m\n\
+             \x1b[38;2;220;50;47mO\x1b[38;2;128;128;128m#\x1b[0m\n",
+            canvas.to_ansi()
+        );
+    }
+
+    #[test]
+    fn test_ppm() {
+        let ppm = canvas().to_ppm(2);
+        let header = b"P6\n4 4\n255\n";
+
+        assert_eq!(header, &ppm[..header.len()]);
+        assert_eq!(header.len() + 4 * 4 * 3, ppm.len());
+        // Second row of pixels, third pixel: the top-right cell.
+        assert_eq!([0, 0, 0], ppm[header.len() + 4 * 3 + 2 * 3..][..3]);
+        // Last row, first pixel: the overlaid bottom-left cell.
+        assert_eq!([220, 50, 47], ppm[header.len() + 3 * 4 * 3..][..3]);
+    }
+
+    #[test]
+    fn test_pbm() {
+        let pbm = canvas().to_pbm(5);
+        let header = b"P4\n10 10\n";
+
+        assert_eq!(header, &pbm[..header.len()]);
+        // Each row of 10 pixels takes two bytes, the second padded with zeros.
+        assert_eq!(header.len() + 10 * 2, pbm.len());
+        assert_eq!([0b1111_1000, 0], pbm[header.len()..][..2]);
+        assert_eq!([0b1111_1111, 0b1100_0000], pbm[header.len() + 5 * 2..][..2]);
+    }
+
+    #[test]
+    fn test_empty() {
+        for (rows, cols) in [(0, 0), (3, 0), (0, 3)] {
+            let canvas = Canvas::new(&Grid::new(rows, cols, false), |_, _| {
+                Style::new('.', Colour::BLACK)
+            });
+
+            assert_eq!("", canvas.to_text());
+            assert_eq!(
+                format!("P6\n{} {}\n255\n", cols * 2, rows * 2).into_bytes(),
+                canvas.to_ppm(2)
+            );
+            assert_eq!(
+                format!("P4\n{} {}\n", cols * 2, rows * 2).into_bytes(),
+                canvas.to_pbm(2)
+            );
+            assert_eq!(b"\x89PNG\r\n\x1a\n", &canvas.to_png(2)[..8]);
+        }
+    }
+
+    #[test]
+    fn test_png() {
+        let png = canvas().to_png(3);
+
+        assert_eq!(b"\x89PNG\r\n\x1a\n", &png[..8]);
+        assert_eq!(b"IHDR", &png[12..16]);
+        assert_eq!([0, 0, 0, 6, 0, 0, 0, 6], png[16..24]);
+        assert_eq!(
+            [0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82],
+            png[png.len() - 12..]
+        );
+    }
+}
@@ -0,0 +1,298 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// Costs and optimal predecessors of every state reached by a search.
+#[derive(Clone, Debug)]
+pub struct ShortestPaths<S> {
+    costs: HashMap<S, usize>,
+    predecessors: HashMap<S, Vec<S>>,
+}
+
+impl<S: Clone + Eq + Hash> ShortestPaths<S> {
+    fn new(starts: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            costs: starts.into_iter().map(|start| (start, 0)).collect(),
+            predecessors: HashMap::new(),
+        }
+    }
+
+    /// Records `to` as reachable from `from` at `cost`. Returns whether `to` should be expanded.
+    /// A state's first predecessor is the one that gave it its cost, which had its own cost first,
+    /// so following first predecessors always ends at a start. Starts never get predecessors, as
+    /// zero-cost steps back to them would otherwise close a loop.
+    fn relax(&mut self, from: &S, to: S, cost: usize) -> bool {
+        match self.costs.get(&to).map(|&old| cost.cmp(&old)) {
+            Some(Ordering::Greater) => false,
+            Some(Ordering::Equal) => {
+                if let Some(predecessors) = self.predecessors.get_mut(&to) {
+                    if !predecessors.contains(from) {
+                        predecessors.push(from.clone());
+                    }
+                }
+
+                false
+            }
+            _ => {
+                self.costs.insert(to.clone(), cost);
+                self.predecessors.insert(to, vec![from.clone()]);
+                true
+            }
+        }
+    }
+
+    pub fn cost(&self, state: &S) -> Option<usize> {
+        self.costs.get(state).copied()
+    }
+
+    pub fn costs(&self) -> &HashMap<S, usize> {
+        &self.costs
+    }
+
+    /// Every predecessor through which `state` is reached at its optimal cost.
+    pub fn predecessors(&self, state: &S) -> &[S] {
+        self.predecessors.get(state).map_or(&[], Vec::as_slice)
+    }
+
+    /// One optimal path from a start state to `target`, both ends included.
+    pub fn path(&self, target: &S) -> Option<Vec<S>> {
+        self.costs.get(target)?;
+
+        let mut path = vec![target.clone()];
+
+        while let Some(prev) = self.predecessors(path.last().unwrap()).first() {
+            path.push(prev.clone());
+        }
+
+        path.reverse();
+
+        Some(path)
+    }
+
+    /// Every state lying on some optimal path to any of `targets`.
+    pub fn optimal_states(&self, targets: impl IntoIterator<Item = S>) -> HashSet<S> {
+        let mut seen = HashSet::new();
+        let mut stack = targets
+            .into_iter()
+            .filter(|target| self.costs.contains_key(target))
+            .collect::<Vec<_>>();
+
+        while let Some(state) = stack.pop() {
+            if seen.insert(state.clone()) {
+                stack.extend(self.predecessors(&state).iter().cloned());
+            }
+        }
+
+        seen
+    }
+}
+
+/// Breadth-first search where every step costs 1.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+) -> ShortestPaths<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut paths = ShortestPaths::new(starts);
+    let mut queue = paths.costs.keys().cloned().collect::<VecDeque<_>>();
+
+    while let Some(u) = queue.pop_front() {
+        let cost = paths.costs[&u] + 1;
+
+        for v in neighbours(&u) {
+            if paths.relax(&u, v.clone(), cost) {
+                queue.push_back(v);
+            }
+        }
+    }
+
+    paths
+}
+
+/// Dijkstra's algorithm over `neighbours`, which yields each neighbouring state and its step cost.
+pub fn dijkstra<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: impl FnMut(&S) -> I,
+) -> ShortestPaths<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    search(starts, neighbours, |_| 0, |_| false).1
+}
+
+/// A* search stopping at the first state satisfying `is_goal`, which is returned alongside the
+/// explored paths. `heuristic` must never overestimate the remaining cost.
+pub fn astar<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> usize,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<(S, ShortestPaths<S>)>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let (goal, paths) = search(starts, neighbours, heuristic, is_goal);
+
+    goal.map(|goal| (goal, paths))
+}
+
+struct Entry<S> {
+    priority: usize,
+    cost: usize,
+    state: S,
+}
+
+impl<S> PartialEq for Entry<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<S> Eq for Entry<S> {}
+
+impl<S> PartialOrd for Entry<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S> Ord for Entry<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Reverse(self.priority).cmp(&Reverse(other.priority))
+    }
+}
+
+fn search<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> usize,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> (Option<S>, ShortestPaths<S>)
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut paths = ShortestPaths::new(starts);
+    let mut heap = paths
+        .costs
+        .keys()
+        .map(|state| Entry {
+            priority: heuristic(state),
+            cost: 0,
+            state: state.clone(),
+        })
+        .collect::<BinaryHeap<_>>();
+
+    while let Some(Entry { cost, state: u, .. }) = heap.pop() {
+        if cost > paths.costs[&u] {
+            continue;
+        }
+
+        if is_goal(&u) {
+            return (Some(u), paths);
+        }
+
+        for (v, weight) in neighbours(&u) {
+            let v_cost = cost + weight;
+
+            if paths.relax(&u, v.clone(), v_cost) {
+                heap.push(Entry {
+                    priority: v_cost + heuristic(&v),
+                    cost: v_cost,
+                    state: v,
+                });
+            }
+        }
+    }
+
+    (None, paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+
+    const MAZE: [&str; 5] = ["..#....", ".##.##.", "...#...", ".#...#.", "...#..."];
+
+    fn open_neighbours(p: &Point) -> Vec<Point> {
+        p.neighbours()
+            .into_iter()
+            .filter(|n| n.in_bounds(5, 7) && MAZE[n.x as usize].as_bytes()[n.y as usize] == b'.')
+            .collect()
+    }
+
+    #[test]
+    fn test_bfs_path() {
+        let paths = bfs([Point::ZERO], open_neighbours);
+        let target = Point::new(0, 6);
+
+        assert_eq!(Some(12), paths.cost(&target));
+
+        let path = paths.path(&target).unwrap();
+        assert_eq!(13, path.len());
+        assert_eq!(Point::ZERO, path[0]);
+        assert!(path.windows(2).all(|w| w[0].manhattan(w[1]) == 1));
+        assert_eq!(None, paths.path(&Point::new(0, 2)));
+    }
+
+    // Steps that cost nothing go both ways between 0 and 1, and from 2 back to the start.
+    #[test]
+    fn test_zero_cost_loops() {
+        let paths = dijkstra([0], |&u| match u {
+            0 => vec![(1, 0)],
+            1 => vec![(0, 0), (2, 1)],
+            _ => vec![(0, 0), (1, 0)],
+        });
+
+        assert_eq!(Some(vec![0, 1, 2]), paths.path(&2));
+        assert_eq!(Some(vec![0, 1]), paths.path(&1));
+        assert_eq!(Some(vec![0]), paths.path(&0));
+        assert!(paths.predecessors(&0).is_empty());
+        assert_eq!(HashSet::from([0, 1, 2]), paths.optimal_states([2]));
+    }
+
+    #[test]
+    fn test_optimal_states() {
+        // Two equally short ways round the wall at (3, 1).
+        let target = Point::new(4, 2);
+        let paths = bfs([Point::new(2, 0)], open_neighbours);
+        let on_best_paths = paths.optimal_states([target]);
+
+        assert_eq!(Some(4), paths.cost(&target));
+        assert_eq!(2, paths.predecessors(&target).len());
+        assert_eq!(8, on_best_paths.len());
+        assert!(on_best_paths.contains(&Point::new(4, 0)));
+        assert!(on_best_paths.contains(&Point::new(2, 2)));
+        assert!(!on_best_paths.contains(&Point::new(1, 0)));
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar_agree() {
+        let weighted = |p: &Point| {
+            open_neighbours(p)
+                .into_iter()
+                .map(|n| (n, 1 + n.y as usize))
+                .collect::<Vec<_>>()
+        };
+        let target = Point::new(4, 6);
+
+        let paths = dijkstra([Point::ZERO], weighted);
+        let (goal, astar_paths) = astar(
+            [Point::ZERO],
+            weighted,
+            |p| p.manhattan(target),
+            |&p| p == target,
+        )
+        .unwrap();
+
+        assert_eq!(target, goal);
+        assert_eq!(paths.cost(&target), astar_paths.cost(&target));
+        assert_eq!(paths.path(&target), astar_paths.path(&target));
+    }
+}
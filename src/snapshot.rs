@@ -0,0 +1,133 @@
+use crate::input;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Where snapshot `name` of `day` is stored, e.g. `src/bin/day15/snapshots/shift_down.snap`.
+pub fn path(day: &str, name: &str) -> PathBuf {
+    input::path(day, "snapshots").join(format!("{name}.snap"))
+}
+
+/// Panics with a diff if `actual` differs from the stored snapshot. With `UPDATE_SNAPSHOTS=1` set,
+/// stores `actual` as the snapshot instead.
+#[track_caller]
+pub fn assert_snapshot(day: &str, name: &str, actual: &str) {
+    let path = path(day, name);
+
+    if env::var("UPDATE_SNAPSHOTS").is_ok_and(|update| update == "1") {
+        fs::create_dir_all(path.parent().unwrap()).expect("Error creating snapshot dir");
+        fs::write(&path, actual).expect("Error writing snapshot");
+        return;
+    }
+
+    let Ok(expected) = fs::read_to_string(&path) else {
+        panic!(
+            "{} is missing, create it with UPDATE_SNAPSHOTS=1",
+            path.display()
+        );
+    };
+
+    if expected != actual {
+        panic!(
+            "{} does not match, accept the change with UPDATE_SNAPSHOTS=1\n{}",
+            path.display(),
+            diff(&expected, actual)
+        );
+    }
+}
+
+/// A line diff from `expected` to `actual`: ` ` for kept lines, `-` for removed and `+` for added.
+/// A line replaced by one of the same width is followed by a row of `^` under the changed columns.
+pub fn diff(expected: &str, actual: &str) -> String {
+    let old = expected.lines().collect::<Vec<_>>();
+    let new = actual.lines().collect::<Vec<_>>();
+
+    // Length of the longest common subsequence of `old[i..]` and `new[j..]`.
+    let mut common = vec![vec![0; new.len() + 1]; old.len() + 1];
+
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut output = String::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            output += &format!(" {}\n", old[i]);
+            i += 1;
+            j += 1;
+            continue;
+        }
+
+        // Gather the whole changed run so that replaced lines can be paired up.
+        let (removed_from, added_from) = (i, j);
+
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() && old[i] == new[j] {
+                break;
+            }
+
+            if j == new.len() || (i < old.len() && common[i + 1][j] >= common[i][j + 1]) {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        let removed = &old[removed_from..i];
+        let added = &new[added_from..j];
+
+        if removed.len() == added.len() {
+            for (before, after) in removed.iter().zip(added) {
+                output += &format!("-{before}\n+{after}\n");
+
+                if before.chars().count() == after.chars().count() {
+                    let marker = before
+                        .chars()
+                        .zip(after.chars())
+                        .map(|(b, a)| if b == a { ' ' } else { '^' })
+                        .collect::<String>();
+
+                    output += &format!(" {}\n", marker.trim_end());
+                }
+            }
+        } else {
+            for line in removed {
+                output += &format!("-{line}\n");
+            }
+
+            for line in added {
+                output += &format!("+{line}\n");
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(" #.#\n #.#\n", diff("#.#\n#.#\n", "#.#\n#.#\n"));
+
+        assert_eq!(
+            " ####\n-#@.#\n+#.@#\n  ^^\n ####\n",
+            diff("####\n#@.#\n####\n", "####\n#.@#\n####\n")
+        );
+
+        assert_eq!(
+            " a\n-b\n-c\n+d\n e\n+f\n",
+            diff("a\nb\nc\ne\n", "a\nd\ne\nf\n")
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::VecDeque;
+
+const ROOT: usize = 0;
+
+#[derive(Clone, Debug, Default)]
+struct Node {
+    children: Vec<(u8, usize)>,
+    pattern: Option<usize>,
+}
+
+/// A byte trie over a set of patterns. Patterns are numbered in order of first insertion.
+#[derive(Clone, Debug)]
+pub struct Trie {
+    nodes: Vec<Node>,
+    lengths: Vec<usize>,
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Trie {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node::default()],
+            lengths: vec![],
+        }
+    }
+
+    fn child(&self, node: usize, byte: u8) -> Option<usize> {
+        self.nodes[node]
+            .children
+            .iter()
+            .find(|&&(b, _)| b == byte)
+            .map(|&(_, child)| child)
+    }
+
+    /// Adds `pattern`, returning its id. Inserting a pattern again returns the original id.
+    pub fn insert(&mut self, pattern: impl AsRef<[u8]>) -> usize {
+        let mut node = ROOT;
+
+        for &byte in pattern.as_ref() {
+            node = match self.child(node, byte) {
+                Some(child) => child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(Node::default());
+                    self.nodes[node].children.push((byte, child));
+                    child
+                }
+            };
+        }
+
+        *self.nodes[node].pattern.get_or_insert_with(|| {
+            self.lengths.push(pattern.as_ref().len());
+            self.lengths.len() - 1
+        })
+    }
+
+    /// Number of distinct patterns.
+    pub fn len(&self) -> usize {
+        self.lengths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lengths.is_empty()
+    }
+
+    pub fn pattern_len(&self, id: usize) -> usize {
+        self.lengths[id]
+    }
+
+    pub fn get(&self, pattern: impl AsRef<[u8]>) -> Option<usize> {
+        let mut node = ROOT;
+
+        for &byte in pattern.as_ref() {
+            node = self.child(node, byte)?;
+        }
+
+        self.nodes[node].pattern
+    }
+
+    pub fn contains(&self, pattern: impl AsRef<[u8]>) -> bool {
+        self.get(pattern).is_some()
+    }
+
+    /// Every pattern that `text` starts with, shortest first, as `(id, length)`.
+    pub fn prefixes<'a>(&'a self, text: &'a [u8]) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let mut node = Some(ROOT);
+        let mut depth = 0;
+
+        std::iter::from_fn(move || loop {
+            let current = node?;
+
+            node = text.get(depth).and_then(|&byte| self.child(current, byte));
+            depth += 1;
+
+            if let Some(id) = self.nodes[current].pattern {
+                return Some((id, depth - 1));
+            }
+        })
+    }
+}
+
+impl<P: AsRef<[u8]>> FromIterator<P> for Trie {
+    fn from_iter<I: IntoIterator<Item = P>>(iter: I) -> Self {
+        let mut trie = Self::new();
+
+        for pattern in iter {
+            trie.insert(pattern);
+        }
+
+        trie
+    }
+}
+
+/// An occurrence of pattern `pattern` at `start..end`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Match {
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// An Aho-Corasick automaton, which finds every occurrence of every pattern in one pass.
+#[derive(Clone, Debug)]
+pub struct AhoCorasick {
+    trie: Trie,
+    fail: Vec<usize>,
+    // The nearest proper suffix of each node that is itself a pattern.
+    output: Vec<Option<usize>>,
+}
+
+impl AhoCorasick {
+    pub fn new(trie: Trie) -> Self {
+        let mut fail = vec![ROOT; trie.nodes.len()];
+        let mut output = vec![None; trie.nodes.len()];
+        let mut queue = VecDeque::from([ROOT]);
+
+        let mut automaton = Self {
+            trie,
+            fail: vec![],
+            output: vec![],
+        };
+
+        while let Some(node) = queue.pop_front() {
+            for &(byte, child) in &automaton.trie.nodes[node].children {
+                if node != ROOT {
+                    let mut suffix = fail[node];
+
+                    fail[child] = loop {
+                        if let Some(next) = automaton.trie.child(suffix, byte) {
+                            break next;
+                        }
+
+                        if suffix == ROOT {
+                            break ROOT;
+                        }
+
+                        suffix = fail[suffix];
+                    };
+                }
+
+                let suffix = fail[child];
+
+                output[child] = if automaton.trie.nodes[suffix].pattern.is_some() {
+                    Some(suffix)
+                } else {
+                    output[suffix]
+                };
+
+                queue.push_back(child);
+            }
+        }
+
+        automaton.fail = fail;
+        automaton.output = output;
+        automaton
+    }
+
+    pub fn trie(&self) -> &Trie {
+        &self.trie
+    }
+
+    fn next(&self, mut node: usize, byte: u8) -> usize {
+        loop {
+            if let Some(child) = self.trie.child(node, byte) {
+                return child;
+            }
+
+            if node == ROOT {
+                return ROOT;
+            }
+
+            node = self.fail[node];
+        }
+    }
+
+    /// Every occurrence of every non-empty pattern in `text`, overlaps included, in order of
+    /// `end`.
+    pub fn find_overlapping<'a>(&'a self, text: &'a [u8]) -> impl Iterator<Item = Match> + 'a {
+        text.iter()
+            .scan(ROOT, move |node, &byte| {
+                *node = self.next(*node, byte);
+                Some(*node)
+            })
+            .enumerate()
+            .flat_map(move |(i, node)| {
+                let mut current = Some(node)
+                    .filter(|&node| node != ROOT && self.trie.nodes[node].pattern.is_some())
+                    .or(self.output[node]);
+
+                std::iter::from_fn(move || {
+                    let node = current.filter(|&node| node != ROOT)?;
+                    let pattern = self.trie.nodes[node].pattern?;
+                    current = self.output[node];
+
+                    Some(Match {
+                        pattern,
+                        start: i + 1 - self.trie.lengths[pattern],
+                        end: i + 1,
+                    })
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trie() {
+        let mut trie = Trie::from_iter(["r", "wr", "b", "g", "bwu", "rb", "gb", "br"]);
+
+        assert_eq!(8, trie.len());
+        assert_eq!(1, trie.insert("wr"));
+        assert_eq!(8, trie.len());
+        assert!(trie.contains("bwu"));
+        assert!(!trie.contains("bw"));
+
+        let b = trie.get("b").unwrap();
+        let br = trie.get("br").unwrap();
+
+        assert_eq!(
+            vec![(b, 1), (br, 2)],
+            trie.prefixes(b"brwrr").collect::<Vec<_>>()
+        );
+        assert_eq!(0, trie.prefixes(b"ubwu").count());
+        assert_eq!(0, trie.prefixes(b"").count());
+    }
+
+    #[test]
+    fn test_aho_corasick() {
+        let trie = Trie::from_iter(["he", "she", "his", "hers"]);
+        let automaton = AhoCorasick::new(trie);
+
+        let matches = automaton
+            .find_overlapping(b"ushers")
+            .map(|m| (automaton.trie().pattern_len(m.pattern), m.start, m.end))
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![(3, 1, 4), (2, 2, 4), (4, 2, 6)], matches);
+    }
+}
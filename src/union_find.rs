@@ -0,0 +1,192 @@
+use crate::point::Point;
+use std::cmp::Ordering;
+
+/// Disjoint sets over `0..len`, with path compression and union by rank.
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    count: usize,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            ranks: vec![0; len],
+            sizes: vec![1; len],
+            count: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The representative of the set containing `x`.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        let mut x = x;
+
+        while self.parents[x] != root {
+            let next = self.parents[x];
+            self.parents[x] = root;
+            x = next;
+        }
+
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`, returning whether they were separate.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let a = self.find(a);
+        let b = self.find(b);
+
+        if a == b {
+            return false;
+        }
+
+        let (root, child) = match self.ranks[a].cmp(&self.ranks[b]) {
+            Ordering::Less => (b, a),
+            Ordering::Greater => (a, b),
+            Ordering::Equal => {
+                self.ranks[a] += 1;
+                (a, b)
+            }
+        };
+
+        self.parents[child] = root;
+        self.sizes[root] += self.sizes[child];
+        self.count -= 1;
+
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Size of the set containing `x`.
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+
+        self.sizes[root]
+    }
+}
+
+/// A [`UnionFind`] over the cells of a `rows` by `cols` grid.
+#[derive(Clone, Debug)]
+pub struct GridUnionFind {
+    rows: usize,
+    cols: usize,
+    sets: UnionFind,
+}
+
+impl GridUnionFind {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            sets: UnionFind::new(rows * cols),
+        }
+    }
+
+    fn index(&self, point: Point) -> usize {
+        point
+            .to_index(self.rows, self.cols)
+            .unwrap_or_else(|| panic!("{point:?} out of bounds for GridUnionFind"))
+    }
+
+    pub fn count(&self) -> usize {
+        self.sets.count()
+    }
+
+    pub fn find(&mut self, point: Point) -> Point {
+        let root = self.sets.find(self.index(point));
+
+        Point::from_index(root, self.cols)
+    }
+
+    pub fn union(&mut self, a: Point, b: Point) -> bool {
+        let (a, b) = (self.index(a), self.index(b));
+
+        self.sets.union(a, b)
+    }
+
+    pub fn connected(&mut self, a: Point, b: Point) -> bool {
+        let (a, b) = (self.index(a), self.index(b));
+
+        self.sets.connected(a, b)
+    }
+
+    pub fn size(&mut self, point: Point) -> usize {
+        let index = self.index(point);
+
+        self.sets.size(index)
+    }
+
+    /// Merges `point` with each in-bounds orthogonal neighbour for which `joins` holds.
+    pub fn union_neighbours(&mut self, point: Point, mut joins: impl FnMut(Point) -> bool) {
+        for neighbour in point.neighbours() {
+            if neighbour.in_bounds(self.rows, self.cols) && joins(neighbour) {
+                self.union(point, neighbour);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(8);
+
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.union(5, 6));
+
+        assert_eq!(4, sets.count());
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 5));
+        assert_eq!(4, sets.size(2));
+        assert_eq!(1, sets.size(7));
+    }
+
+    #[test]
+    fn test_grid_regions() {
+        let grid = ["AAB", "ABB", "CCB"].map(str::as_bytes);
+        let plot = |p: Point| grid[p.x as usize][p.y as usize];
+
+        let mut sets = GridUnionFind::new(3, 3);
+
+        for index in 0..9 {
+            let point = Point::from_index(index, 3);
+            sets.union_neighbours(point, |neighbour| plot(neighbour) == plot(point));
+        }
+
+        assert_eq!(3, sets.count());
+        assert_eq!(4, sets.size(Point::new(0, 2)));
+        assert!(sets.connected(Point::new(0, 0), Point::new(1, 0)));
+        assert!(!sets.connected(Point::new(2, 0), Point::new(1, 0)));
+        assert_eq!(sets.find(Point::new(2, 1)), sets.find(Point::new(2, 0)));
+    }
+}
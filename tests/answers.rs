@@ -0,0 +1,223 @@
+use advent_of_code_2024::input;
+use advent_of_code_2024::parse::{Input, ParseError};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+
+// Answers by day and input hash, one `<day> <hash> <part 1> <part 2>` per line.
+type Registry = BTreeMap<(String, String), (String, String)>;
+
+const HEADER: &str = "\
+# Answers by day and input hash, checked by `cargo test --test answers`.
+# This starts empty: the puzzle inputs are not in the repository, so their hashes are not known.
+# Days 01 to 23 assert their answers for the original inputs in `main`. Once an input's answers are
+# known to be right, record them with `UPDATE_ANSWERS=1 cargo test --test answers`. A day without a
+# second part records `-` for it.
+";
+
+// Recorded for a day that prints no answer for its second part.
+const NO_ANSWER: &str = "-";
+
+fn registry_path() -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "tests", "answers.txt"]
+        .iter()
+        .collect()
+}
+
+fn parse_registry(text: &str) -> Result<Registry, ParseError> {
+    let input = Input::new(text);
+
+    input
+        .lines(text)
+        .filter(|line| !line.starts_with('#'))
+        .map(
+            |line| match line.split_ascii_whitespace().collect::<Vec<_>>()[..] {
+                [day, hash, part1, part2] => Ok((
+                    (day.to_owned(), hash.to_owned()),
+                    (part1.to_owned(), part2.to_owned()),
+                )),
+                _ => Err(input.error(line, "<day> <hash> <part 1> <part 2>")),
+            },
+        )
+        .collect()
+}
+
+fn format_registry(registry: &Registry) -> String {
+    let mut text = HEADER.to_owned();
+
+    for ((day, hash), (part1, part2)) in registry {
+        text += &format!("{day} {hash} {part1} {part2}\n");
+    }
+
+    text
+}
+
+// FNV-1a, which is stable across platforms and Rust versions, unlike `DefaultHasher`.
+fn hash(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf29ce484222325u64, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{hash:016x}")
+}
+
+// Every day with a binary, e.g. `day06`.
+fn implemented_days() -> Vec<String> {
+    let bin = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("bin");
+
+    let mut days = fs::read_dir(bin)
+        .expect("Error reading src/bin")
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let day = path.file_name()?.to_str()?.to_owned();
+
+            (day.starts_with("day") && path.join("main.rs").exists()).then_some(day)
+        })
+        .collect::<Vec<_>>();
+
+    days.sort_unstable();
+    days
+}
+
+// Runs the binary for `day` on its input and picks the answers out of what it prints.
+fn run(day: &str) -> Result<(String, String), String> {
+    // Cargo builds every binary before running integration tests, all into the same directory.
+    let exe = Path::new(env!("CARGO_BIN_EXE_day01")).with_file_name(day);
+    let output = Command::new(&exe)
+        .output()
+        .map_err(|err| format!("{}: {err}", exe.display()))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    if !output.status.success() {
+        return Err(format!(
+            "exited with {}\n{stdout}{}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let answer = |prefix| {
+        stdout
+            .lines()
+            .find_map(|line| line.strip_prefix(prefix))
+            .map(str::to_owned)
+            .ok_or_else(|| format!("printed no {prefix:?} line\n{stdout}"))
+    };
+
+    // A missing second part is compared like any other answer, so a day that stops printing one
+    // fails against its recorded answer.
+    let part2 = answer("Part 2: ").unwrap_or_else(|_| NO_ANSWER.to_owned());
+
+    Ok((answer("Part 1: ")?, part2))
+}
+
+#[test]
+fn test_answers() {
+    let update = env::var("UPDATE_ANSWERS").is_ok_and(|update| update == "1");
+    let text = fs::read_to_string(registry_path()).unwrap_or_default();
+    let mut registry = parse_registry(&text).unwrap_or_else(|err| panic!("answers.txt: {err}"));
+
+    let days = implemented_days();
+
+    let (present, absent): (Vec<_>, Vec<_>) = days
+        .iter()
+        .map(|day| (day, input::try_read(day)))
+        .partition(|(_, input)| input.is_some());
+
+    if present.is_empty() {
+        println!("Skipping, no day has an input.txt");
+        return;
+    }
+
+    if !absent.is_empty() {
+        let absent = absent
+            .iter()
+            .map(|(day, _)| day.as_str())
+            .collect::<Vec<_>>();
+        println!("Skipping {}, which have no input.txt", absent.join(", "));
+    }
+
+    // Debug builds of the slower days take a while, so they all run at once.
+    let results = thread::scope(|scope| {
+        let handles = present
+            .iter()
+            .map(|(day, input)| {
+                let key = (day.to_string(), hash(input.as_deref().unwrap()));
+                (key, scope.spawn(move || run(day)))
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .map(|(key, handle)| (key, handle.join().unwrap()))
+            .collect::<Vec<_>>()
+    });
+
+    let mut failures = vec![];
+
+    for ((day, hash), result) in results {
+        let actual = match result {
+            Ok(actual) => actual,
+            Err(err) => {
+                failures.push(format!("{day} {err}"));
+                continue;
+            }
+        };
+
+        match registry.get(&(day.clone(), hash.clone())) {
+            Some(expected) if *expected == actual => {}
+            _ if update => {
+                registry.insert((day, hash), actual);
+            }
+            Some(expected) => {
+                failures.push(format!("{day} answered {actual:?}, expected {expected:?}"))
+            }
+            None => failures.push(format!(
+                "{day} has no answers for input {hash}, which answers {actual:?}"
+            )),
+        }
+    }
+
+    for (day, hash) in registry.keys() {
+        if !days.contains(day) {
+            failures.push(format!(
+                "{day} is in answers.txt as {hash}, but has no binary"
+            ));
+        }
+    }
+
+    if update {
+        fs::write(registry_path(), format_registry(&registry)).expect("Error writing answers.txt");
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{}\nRecord new answers with UPDATE_ANSWERS=1",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_registry_round_trip() {
+    let text = format!(
+        "{HEADER}day01 0123456789abcdef 11 31\nday17 fedcba9876543210 4,6,3 117440\nday25 0011223344556677 3 -\n"
+    );
+    let registry = parse_registry(&text).unwrap();
+
+    assert_eq!(
+        Some(&("4,6,3".to_owned(), "117440".to_owned())),
+        registry.get(&("day17".to_owned(), "fedcba9876543210".to_owned()))
+    );
+    assert_eq!(
+        Some(&("3".to_owned(), NO_ANSWER.to_owned())),
+        registry.get(&("day25".to_owned(), "0011223344556677".to_owned()))
+    );
+    assert_eq!(text, format_registry(&registry));
+    assert!(parse_registry("day01 0123456789abcdef 11\n").is_err());
+}